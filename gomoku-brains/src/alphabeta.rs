@@ -0,0 +1,148 @@
+use std::time::{Duration, Instant};
+
+use gomoku_core::{
+    board::{Board, Cell},
+    location::Location,
+};
+
+use crate::{
+    pattern::{self, FIVE},
+    protocol::{Brain, BrainSettings},
+};
+
+/// Moves searched at each node, the most promising first
+const WIDTH: usize = 10;
+const MAX_DEPTH: usize = 6;
+const DEFAULT_MOVE_TIME: Duration = Duration::from_secs(1);
+
+const WIN: i64 = FIVE as i64 * 10;
+
+/// Threat-based alpha-beta search with iterative deepening
+#[derive(Default)]
+pub struct AlphaBetaBrain {
+    /// Depth and value of the last completed search
+    last_search: Option<(usize, i64)>,
+}
+
+struct Search {
+    deadline: Instant,
+    timed_out: bool,
+}
+
+impl Search {
+    /// Pattern balance of the position, for `cell` to move
+    fn evaluate(board: &Board, cell: Cell) -> i64 {
+        let best = |cell: Cell| {
+            pattern::candidates(board, 1)
+                .iter()
+                .map(|location| pattern::score(board, location.x, location.y, cell))
+                .max()
+                .unwrap_or(0) as i64
+        };
+
+        best(cell) - best(pattern::opponent(cell)) / 2
+    }
+
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        depth: usize,
+        mut alpha: i64,
+        beta: i64,
+        cell: Cell,
+    ) -> i64 {
+        if depth == 0 {
+            return Self::evaluate(board, cell);
+        }
+        if Instant::now() >= self.deadline {
+            self.timed_out = true;
+            return 0;
+        }
+
+        let moves = pattern::ranked_candidates(board, cell);
+        if moves.is_empty() {
+            return 0;
+        }
+
+        let mut best = -WIN * 2;
+        for (location, _) in moves.into_iter().take(WIDTH) {
+            let value = if pattern::is_five(board, location.x, location.y, cell) {
+                WIN + depth as i64
+            } else {
+                board.place(location.x, location.y, cell);
+                let value = -self.negamax(board, depth - 1, -beta, -alpha, pattern::opponent(cell));
+                board.remove(location.x, location.y);
+                value
+            };
+
+            best = best.max(value);
+            alpha = alpha.max(value);
+            if alpha >= beta || self.timed_out {
+                break;
+            }
+        }
+        best
+    }
+
+    fn root(&mut self, board: &mut Board, depth: usize) -> Option<(Location, i64)> {
+        let mut best: Option<(Location, i64)> = None;
+
+        for (location, _) in pattern::ranked_candidates(board, Cell::Ai1)
+            .into_iter()
+            .take(WIDTH)
+        {
+            let value = if pattern::is_five(board, location.x, location.y, Cell::Ai1) {
+                WIN + depth as i64
+            } else {
+                let alpha = best.map_or(-WIN * 2, |(_, value)| value);
+                board.place(location.x, location.y, Cell::Ai1);
+                let value = -self.negamax(board, depth - 1, -WIN * 2, -alpha, Cell::Ai2);
+                board.remove(location.x, location.y);
+                value
+            };
+
+            if self.timed_out {
+                return None;
+            }
+            if best.is_none_or(|(_, best)| value > best) {
+                best = Some((location, value));
+            }
+        }
+
+        best
+    }
+}
+
+impl Brain for AlphaBetaBrain {
+    fn name(&self) -> &str {
+        "alphabeta"
+    }
+
+    fn play(&mut self, board: &Board, settings: &BrainSettings) -> Location {
+        let mut board = board.clone();
+        // Keep a margin for the answer to reach the referee
+        let move_time = settings.move_time(DEFAULT_MOVE_TIME) / 2;
+        let mut search = Search {
+            deadline: Instant::now() + move_time,
+            timed_out: false,
+        };
+
+        let mut chosen = pattern::ranked_candidates(&board, Cell::Ai1)[0].0;
+        self.last_search = None;
+        for depth in 1..=MAX_DEPTH {
+            match search.root(&mut board, depth) {
+                Some((location, value)) => {
+                    chosen = location;
+                    self.last_search = Some((depth, value));
+                }
+                None => break,
+            }
+        }
+        chosen
+    }
+
+    fn message(&self) -> Option<String> {
+        self.last_search
+            .map(|(depth, value)| format!("depth {} eval {}", depth, value))
+    }
+}
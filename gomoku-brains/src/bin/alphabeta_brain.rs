@@ -0,0 +1,5 @@
+use gomoku_brains::{alphabeta::AlphaBetaBrain, protocol};
+
+fn main() {
+    protocol::run(&mut AlphaBetaBrain::default());
+}
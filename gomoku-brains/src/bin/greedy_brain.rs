@@ -0,0 +1,5 @@
+use gomoku_brains::{greedy::GreedyBrain, protocol};
+
+fn main() {
+    protocol::run(&mut GreedyBrain::default());
+}
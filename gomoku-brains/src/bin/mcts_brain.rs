@@ -0,0 +1,55 @@
+use std::{env, process, time::Duration};
+
+use gomoku_brains::{
+    mcts::{MctsBrain, MctsSettings, Playout},
+    protocol,
+};
+
+fn show_help() {
+    let messages = [
+        "Monte Carlo tree search brain, speaking the Gomocup protocol",
+        "",
+        "Usage: mcts_brain [OPTIONS]",
+        "",
+        "Options:",
+        "\t--playouts <COUNT>\t\tPlayouts per move, unbounded by default",
+        "\t--time-limit <MS>\t\tTime per move unless INFO gives less (1000 by default)",
+        "\t--random-playouts\t\tPlay uniformly random playouts instead of pattern biased ones",
+    ];
+
+    for message in messages {
+        eprintln!("{}", message);
+    }
+}
+
+fn parse_number(value: Option<&String>) -> usize {
+    match value.and_then(|value| value.parse().ok()) {
+        Some(number) => number,
+        None => {
+            show_help();
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut settings = MctsSettings::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--playouts" => settings.playouts = Some(parse_number(args.next())),
+            "--time-limit" => {
+                settings.time_limit = Duration::from_millis(parse_number(args.next()) as u64)
+            }
+            "--random-playouts" => settings.playout = Playout::Random,
+            _ => {
+                show_help();
+                process::exit(1);
+            }
+        }
+    }
+
+    protocol::run(&mut MctsBrain::new(settings));
+}
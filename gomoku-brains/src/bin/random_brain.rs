@@ -0,0 +1,5 @@
+use gomoku_brains::{protocol, random::RandomBrain};
+
+fn main() {
+    protocol::run(&mut RandomBrain::default());
+}
@@ -0,0 +1,23 @@
+use gomoku_core::{
+    board::{Board, Cell},
+    location::Location,
+};
+
+use crate::{
+    pattern,
+    protocol::{Brain, BrainSettings},
+};
+
+/// Plays the move with the best pattern score, looking one ply ahead
+#[derive(Default)]
+pub struct GreedyBrain {}
+
+impl Brain for GreedyBrain {
+    fn name(&self) -> &str {
+        "greedy"
+    }
+
+    fn play(&mut self, board: &Board, _settings: &BrainSettings) -> Location {
+        pattern::ranked_candidates(board, Cell::Ai1)[0].0
+    }
+}
@@ -0,0 +1,6 @@
+pub mod alphabeta;
+pub mod greedy;
+pub mod mcts;
+pub mod pattern;
+pub mod protocol;
+pub mod random;
@@ -0,0 +1,221 @@
+use std::time::{Duration, Instant};
+
+use gomoku_core::{
+    board::{Board, Cell},
+    location::Location,
+};
+use rand::{rngs::ThreadRng, seq::SliceRandom, Rng};
+
+use crate::{
+    pattern,
+    protocol::{Brain, BrainSettings},
+};
+
+const EXPLORATION: f64 = 1.4;
+/// Cells looked at when a biased playout picks a move
+const BIAS_SAMPLES: usize = 6;
+const DEFAULT_MOVE_TIME: Duration = Duration::from_secs(1);
+
+pub enum Playout {
+    /// Any free cell, uniformly at random
+    Random,
+    /// Best pattern score among a few random free cells
+    PatternBiased,
+}
+
+pub struct MctsSettings {
+    pub playout: Playout,
+    /// Playouts per move, bounded by time only if `None`
+    pub playouts: Option<usize>,
+    /// Time spent per move, unless the referee gives less
+    pub time_limit: Duration,
+}
+
+impl Default for MctsSettings {
+    fn default() -> Self {
+        Self {
+            playout: Playout::PatternBiased,
+            playouts: None,
+            time_limit: DEFAULT_MOVE_TIME,
+        }
+    }
+}
+
+struct Node {
+    /// Move leading to this node, and who played it
+    location: Option<Location>,
+    cell: Cell,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Location>,
+    visits: u32,
+    /// Playouts won by `cell`, a draw counting as half a win
+    wins: f64,
+    /// Whether the game ends with this move, and who won it then
+    over: bool,
+    winner: Option<Cell>,
+}
+
+impl Node {
+    fn new(
+        location: Option<Location>,
+        cell: Cell,
+        parent: Option<usize>,
+        board: &Board,
+        five: bool,
+    ) -> Self {
+        let over = five || board.is_full();
+        Self {
+            location,
+            cell,
+            parent,
+            children: Vec::new(),
+            untried: if over {
+                Vec::new()
+            } else {
+                pattern::candidates(board, 1)
+            },
+            visits: 0,
+            wins: 0.0,
+            over,
+            winner: five.then_some(cell),
+        }
+    }
+
+    fn uct(&self, parent_visits: u32) -> f64 {
+        self.wins / self.visits as f64
+            + EXPLORATION * ((parent_visits as f64).ln() / self.visits as f64).sqrt()
+    }
+}
+
+/// Monte Carlo tree search with UCT selection
+pub struct MctsBrain {
+    pub settings: MctsSettings,
+    rng: ThreadRng,
+}
+
+impl MctsBrain {
+    pub fn new(settings: MctsSettings) -> Self {
+        Self {
+            settings,
+            rng: rand::thread_rng(),
+        }
+    }
+
+    fn playout_move(&mut self, board: &Board, free: &mut Vec<Location>, cell: Cell) -> Location {
+        let index = match self.settings.playout {
+            Playout::Random => self.rng.gen_range(0..free.len()),
+            Playout::PatternBiased => (0..BIAS_SAMPLES.min(free.len()))
+                .map(|_| self.rng.gen_range(0..free.len()))
+                .max_by_key(|index| {
+                    pattern::move_score(board, free[*index].x, free[*index].y, cell)
+                })
+                .unwrap(),
+        };
+        free.swap_remove(index)
+    }
+
+    /// Play randomly until the end, returning the winner
+    fn playout(&mut self, board: &mut Board, mut cell: Cell) -> Option<Cell> {
+        let mut free = Vec::new();
+        for y in 0..board.size() {
+            for x in 0..board.size() {
+                if board.get(x, y) == Cell::Empty {
+                    free.push(Location { x, y });
+                }
+            }
+        }
+        free.shuffle(&mut self.rng);
+
+        while !free.is_empty() {
+            let location = self.playout_move(board, &mut free, cell);
+            if pattern::is_five(board, location.x, location.y, cell) {
+                return Some(cell);
+            }
+            board.place(location.x, location.y, cell);
+            cell = pattern::opponent(cell);
+        }
+        None
+    }
+
+    fn iterate(&mut self, tree: &mut Vec<Node>, root: &Board) {
+        let mut board = root.clone();
+        let mut node = 0;
+
+        // Selection
+        while !tree[node].over && tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+            let visits = tree[node].visits;
+            node = *tree[node]
+                .children
+                .iter()
+                .max_by(|a, b| tree[**a].uct(visits).total_cmp(&tree[**b].uct(visits)))
+                .unwrap();
+            let location = tree[node].location.unwrap();
+            board.place(location.x, location.y, tree[node].cell);
+        }
+
+        // Expansion
+        if !tree[node].untried.is_empty() {
+            let index = self.rng.gen_range(0..tree[node].untried.len());
+            let location = tree[node].untried.swap_remove(index);
+            let cell = pattern::opponent(tree[node].cell);
+            let five = pattern::is_five(&board, location.x, location.y, cell);
+            board.place(location.x, location.y, cell);
+
+            tree.push(Node::new(Some(location), cell, Some(node), &board, five));
+            let child = tree.len() - 1;
+            tree[node].children.push(child);
+            node = child;
+        }
+
+        // Simulation
+        let winner = if tree[node].over {
+            tree[node].winner
+        } else {
+            self.playout(&mut board, pattern::opponent(tree[node].cell))
+        };
+
+        // Backpropagation
+        let mut current = Some(node);
+        while let Some(index) = current {
+            tree[index].visits += 1;
+            tree[index].wins += match winner {
+                Some(cell) if cell == tree[index].cell => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            current = tree[index].parent;
+        }
+    }
+}
+
+impl Brain for MctsBrain {
+    fn name(&self) -> &str {
+        "mcts"
+    }
+
+    fn play(&mut self, board: &Board, settings: &BrainSettings) -> Location {
+        // Keep a margin for the answer to reach the referee
+        let move_time = settings
+            .move_time(self.settings.time_limit)
+            .min(self.settings.time_limit)
+            * 4
+            / 5;
+        let deadline = Instant::now() + move_time;
+
+        // The root holds the last opponent move, whoever actually played it
+        let mut tree = vec![Node::new(None, Cell::Ai2, None, board, false)];
+        let mut playouts = 0;
+        while Instant::now() < deadline && self.settings.playouts.is_none_or(|max| playouts < max) {
+            self.iterate(&mut tree, board);
+            playouts += 1;
+        }
+
+        tree[0]
+            .children
+            .iter()
+            .max_by_key(|child| tree[**child].visits)
+            .and_then(|child| tree[*child].location)
+            .unwrap_or_else(|| pattern::ranked_candidates(board, Cell::Ai1)[0].0)
+    }
+}
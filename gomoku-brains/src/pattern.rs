@@ -0,0 +1,133 @@
+use gomoku_core::{
+    board::{Board, Cell},
+    location::Location,
+};
+
+/// Row, column, diagonal and anti-diagonal
+pub const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+pub const FIVE: u32 = 1_000_000;
+
+pub fn opponent(cell: Cell) -> Cell {
+    match cell {
+        Cell::Ai1 => Cell::Ai2,
+        _ => Cell::Ai1,
+    }
+}
+
+fn cell_at(board: &Board, x: isize, y: isize) -> Option<Cell> {
+    let size = board.size() as isize;
+    if x < 0 || y < 0 || x >= size || y >= size {
+        return None;
+    }
+    Some(board.get(x as usize, y as usize))
+}
+
+/// Length of the line of `cell` going through (x, y) as if `cell` was played there,
+/// along with how many of its two ends are open
+pub fn line(board: &Board, x: usize, y: usize, cell: Cell, (dx, dy): (isize, isize)) -> (u32, u32) {
+    let mut count = 1;
+    let mut open = 0;
+
+    for sign in [1, -1] {
+        let (mut cx, mut cy) = (x as isize + sign * dx, y as isize + sign * dy);
+        while cell_at(board, cx, cy) == Some(cell) {
+            count += 1;
+            cx += sign * dx;
+            cy += sign * dy;
+        }
+        if cell_at(board, cx, cy) == Some(Cell::Empty) {
+            open += 1;
+        }
+    }
+
+    (count, open)
+}
+
+fn line_score(count: u32, open: u32) -> u32 {
+    match (count, open) {
+        (5.., _) => FIVE,
+        (4, 2) => 100_000,
+        (4, 1) => 10_000,
+        (3, 2) => 5_000,
+        (3, 1) => 500,
+        (2, 2) => 200,
+        (2, 1) => 20,
+        (1, 2) => 10,
+        (1, 1) => 1,
+        _ => 0,
+    }
+}
+
+/// How good playing `cell` at (x, y) is for `cell`
+pub fn score(board: &Board, x: usize, y: usize, cell: Cell) -> u32 {
+    DIRECTIONS
+        .iter()
+        .map(|direction| {
+            let (count, open) = line(board, x, y, cell, *direction);
+            line_score(count, open)
+        })
+        .fold(0u32, |total, score| total.saturating_add(score))
+}
+
+/// Score of (x, y) both as an attack for `cell` and as a block against its opponent
+pub fn move_score(board: &Board, x: usize, y: usize, cell: Cell) -> u32 {
+    let attack = score(board, x, y, cell);
+    let defense = score(board, x, y, opponent(cell));
+    attack.saturating_add(defense - defense / 10)
+}
+
+/// Whether playing `cell` at (x, y) makes five in a row
+pub fn is_five(board: &Board, x: usize, y: usize, cell: Cell) -> bool {
+    DIRECTIONS
+        .iter()
+        .any(|direction| line(board, x, y, cell, *direction).0 >= 5)
+}
+
+/// Free cells at most `distance` away from a stone, or the center on an empty board
+pub fn candidates(board: &Board, distance: usize) -> Vec<Location> {
+    let size = board.size();
+    let mut near = vec![false; size * size];
+    let mut any_stone = false;
+
+    for y in 0..size {
+        for x in 0..size {
+            if board.get(x, y) == Cell::Empty {
+                continue;
+            }
+            any_stone = true;
+            for ny in y.saturating_sub(distance)..(y + distance + 1).min(size) {
+                for nx in x.saturating_sub(distance)..(x + distance + 1).min(size) {
+                    near[ny * size + nx] = true;
+                }
+            }
+        }
+    }
+
+    if !any_stone {
+        return vec![(size / 2, size / 2).into()];
+    }
+
+    let mut locations = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            if near[y * size + x] && board.get(x, y) == Cell::Empty {
+                locations.push((x, y).into());
+            }
+        }
+    }
+    if locations.is_empty() && distance < size {
+        return candidates(board, size);
+    }
+    locations
+}
+
+/// Candidates sorted from the most to the least promising for `cell`
+pub fn ranked_candidates(board: &Board, cell: Cell) -> Vec<(Location, u32)> {
+    let mut ranked: Vec<(Location, u32)> = candidates(board, 2)
+        .into_iter()
+        .map(|location| (location, move_score(board, location.x, location.y, cell)))
+        .collect();
+    ranked.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    ranked
+}
@@ -0,0 +1,205 @@
+use std::{
+    io::{self, BufRead, Write},
+    time::Duration,
+};
+
+use gomoku_core::{
+    board::{Board, Cell},
+    location::Location,
+};
+
+/// Settings given by the referee through `INFO`
+#[derive(Default)]
+pub struct BrainSettings {
+    pub timeout_turn: Option<Duration>,
+    pub timeout_match: Option<Duration>,
+    pub time_left: Option<Duration>,
+}
+
+impl BrainSettings {
+    /// Time the brain can spend on the current move
+    pub fn move_time(&self, default: Duration) -> Duration {
+        let mut time = self.timeout_turn.unwrap_or(default);
+        if let Some(time_left) = self.time_left {
+            time = time.min(time_left / 10);
+        }
+        time
+    }
+}
+
+/// Brain playing through the Gomocup protocol
+///
+/// Stones of the brain are `Cell::Ai1`, stones of its opponent `Cell::Ai2`.
+pub trait Brain {
+    fn name(&self) -> &str;
+
+    /// Choose a move on `board`, which always has a free cell left
+    fn play(&mut self, board: &Board, settings: &BrainSettings) -> Location;
+
+    /// Sent as `MESSAGE` along with the last move played, such as its evaluation
+    fn message(&self) -> Option<String> {
+        None
+    }
+}
+
+struct Session<'a> {
+    brain: &'a mut dyn Brain,
+    board: Option<Board>,
+    settings: BrainSettings,
+}
+
+impl Session<'_> {
+    fn play(&mut self) -> String {
+        let board = match &mut self.board {
+            Some(board) if !board.is_full() => board,
+            Some(_) => return "ERROR board is full".to_string(),
+            None => return "ERROR START was not sent".to_string(),
+        };
+
+        let location = self.brain.play(board, &self.settings);
+        board.place(location.x, location.y, Cell::Ai1);
+        match self.brain.message() {
+            Some(message) => format!("MESSAGE {}\n{}", message, location),
+            None => location.to_string(),
+        }
+    }
+
+    fn place(&mut self, coords: &str, cell: Cell) -> Result<(), String> {
+        let location = Location::from_string(coords.to_string())?;
+        match &mut self.board {
+            Some(board) if board.is_free(location.x, location.y) => {
+                board.place(location.x, location.y, cell);
+                Ok(())
+            }
+            Some(_) => Err(format!("cannot place a stone at {}", location)),
+            None => Err("START was not sent".to_string()),
+        }
+    }
+
+    fn info(&mut self, key: &str, value: &str) {
+        let millis = match value.parse::<u64>() {
+            Ok(millis) => millis,
+            Err(_) => return,
+        };
+        // 0 means no limit for the turn and match timeouts
+        let duration = (millis > 0).then(|| Duration::from_millis(millis));
+
+        match key {
+            "timeout_turn" => self.settings.timeout_turn = duration,
+            "timeout_match" => self.settings.timeout_match = duration,
+            "time_left" => self.settings.time_left = Some(Duration::from_millis(millis)),
+            _ => {}
+        }
+    }
+
+    /// Handle one command, returning the answer to print if any
+    fn handle(
+        &mut self,
+        command: &str,
+        lines: &mut impl Iterator<Item = String>,
+    ) -> Option<String> {
+        let mut words = command.split_whitespace();
+        let keyword = words.next().unwrap_or("").to_uppercase();
+        let argument = words.next().unwrap_or("");
+
+        let answer = match keyword.as_str() {
+            "START" => match argument.parse::<usize>() {
+                Ok(size) if size >= 5 => {
+                    self.board = Some(Board::new(size));
+                    "OK".to_string()
+                }
+                _ => format!("ERROR unsupported size [{}]", argument),
+            },
+            "RESTART" => match &self.board {
+                Some(board) => {
+                    self.board = Some(Board::new(board.size()));
+                    "OK".to_string()
+                }
+                None => "ERROR START was not sent".to_string(),
+            },
+            "BEGIN" => self.play(),
+            "TURN" => match self.place(argument, Cell::Ai2) {
+                Ok(_) => self.play(),
+                Err(err) => format!("ERROR {}", err),
+            },
+            "BOARD" => {
+                if let Some(board) = &self.board {
+                    self.board = Some(Board::new(board.size()));
+                }
+                for line in lines.by_ref() {
+                    let line = line.trim().to_string();
+                    if line.eq_ignore_ascii_case("DONE") {
+                        break;
+                    }
+                    let fields: Vec<&str> = line.split(',').collect();
+                    if fields.len() != 3 {
+                        continue;
+                    }
+                    let cell = match fields[2] {
+                        "1" => Cell::Ai1,
+                        _ => Cell::Ai2,
+                    };
+                    let _ = self.place(&format!("{},{}", fields[0], fields[1]), cell);
+                }
+                self.play()
+            }
+            "TAKEBACK" => match (Location::from_string(argument.to_string()), &mut self.board) {
+                (Ok(location), Some(board))
+                    if location.x < board.size() && location.y < board.size() =>
+                {
+                    board.remove(location.x, location.y);
+                    "OK".to_string()
+                }
+                _ => format!("ERROR cannot take back [{}]", argument),
+            },
+            "INFO" => {
+                self.info(argument, words.next().unwrap_or(""));
+                return None;
+            }
+            "ABOUT" => format!(
+                "name=\"{}\", version=\"{}\", author=\"gomoku_player\"",
+                self.brain.name(),
+                env!("CARGO_PKG_VERSION")
+            ),
+            "" => return None,
+            _ => format!("UNKNOWN {}", command),
+        };
+
+        Some(answer)
+    }
+}
+
+/// Play with `brain` on the standard input/output until `END` is received
+pub fn run(brain: &mut dyn Brain) {
+    let stdin = io::stdin();
+    if let Err(err) = serve(brain, stdin.lock(), &mut io::stdout()) {
+        eprintln!("Error whilst answering the referee: [{}]", err);
+    }
+}
+
+/// Play with `brain`, reading commands from `input` until `END` is received
+pub fn serve(
+    brain: &mut dyn Brain,
+    input: impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let mut session = Session {
+        brain,
+        board: None,
+        settings: BrainSettings::default(),
+    };
+
+    let mut lines = input.lines().map_while(Result::ok);
+
+    while let Some(command) = lines.next() {
+        let command = command.trim().to_string();
+        if command.eq_ignore_ascii_case("END") {
+            break;
+        }
+        if let Some(answer) = session.handle(&command, &mut lines) {
+            writeln!(output, "{}", answer)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use gomoku_core::{
+    board::{Board, Cell},
+    location::Location,
+};
+use rand::seq::SliceRandom;
+
+use crate::protocol::{Brain, BrainSettings};
+
+/// Plays any free cell, uniformly at random
+#[derive(Default)]
+pub struct RandomBrain {}
+
+impl Brain for RandomBrain {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn play(&mut self, board: &Board, _settings: &BrainSettings) -> Location {
+        let mut free = Vec::new();
+        for y in 0..board.size() {
+            for x in 0..board.size() {
+                if board.get(x, y) == Cell::Empty {
+                    free.push(Location { x, y });
+                }
+            }
+        }
+
+        *free
+            .choose(&mut rand::thread_rng())
+            .expect("the board has no free cell")
+    }
+}
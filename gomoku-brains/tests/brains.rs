@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use gomoku_brains::{
+    alphabeta::AlphaBetaBrain,
+    greedy::GreedyBrain,
+    mcts::{MctsBrain, MctsSettings},
+    protocol::{self, Brain, BrainSettings},
+    random::RandomBrain,
+};
+use gomoku_core::{
+    ai::Ai,
+    board::{Board, Cell},
+    location::Location,
+};
+
+/// Answers of `brain` to the referee's `commands`, one line each
+fn session(brain: &mut dyn Brain, commands: &[&str]) -> Vec<String> {
+    let input = commands.join("\n");
+    let mut output = Vec::new();
+    protocol::serve(brain, input.as_bytes(), &mut output).unwrap();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Board with the brain's stones (`Cell::Ai1`) and its opponent's
+fn board(size: usize, own: &[(usize, usize)], opponent: &[(usize, usize)]) -> Board {
+    let mut board = Board::new(size);
+    for &(x, y) in own {
+        board.place(x, y, Cell::Ai1);
+    }
+    for &(x, y) in opponent {
+        board.place(x, y, Cell::Ai2);
+    }
+    board
+}
+
+fn quick() -> BrainSettings {
+    BrainSettings {
+        timeout_turn: Some(Duration::from_millis(400)),
+        ..BrainSettings::default()
+    }
+}
+
+fn location(answer: &str) -> Location {
+    Location::from_string(answer.to_string()).unwrap()
+}
+
+#[test]
+fn session_answers_every_command() {
+    let answers = session(
+        &mut GreedyBrain::default(),
+        &[
+            "ABOUT",
+            "START 15",
+            "INFO timeout_turn 1000",
+            "BEGIN",
+            "TURN 0,0",
+            "TAKEBACK 0,0",
+            "RESTART",
+            "YXSHOWFORBID",
+            "END",
+            "BEGIN",
+        ],
+    );
+
+    assert_eq!(answers.len(), 7);
+    assert!(answers[0].starts_with("name=\"greedy\""));
+    assert_eq!(answers[1], "OK");
+    let first = location(&answers[2]);
+    let second = location(&answers[3]);
+    assert_ne!(first, second);
+    assert_ne!(second, (0, 0).into());
+    assert_eq!(answers[4], "OK");
+    assert_eq!(answers[5], "OK");
+    assert_eq!(answers[6], "UNKNOWN YXSHOWFORBID");
+}
+
+#[test]
+fn session_rejects_commands_out_of_place() {
+    let answers = session(
+        &mut RandomBrain::default(),
+        &[
+            "BEGIN",
+            "START 3",
+            "START 10",
+            "TURN 20,20",
+            "TAKEBACK 30,1",
+        ],
+    );
+
+    assert_eq!(
+        answers,
+        [
+            "ERROR START was not sent",
+            "ERROR unsupported size [3]",
+            "OK",
+            "ERROR cannot place a stone at 20,20",
+            "ERROR cannot take back [30,1]",
+        ]
+    );
+}
+
+#[test]
+fn session_plays_on_the_board_it_is_sent() {
+    // Owner 1 stones are the brain's, it completes its four
+    let answers = session(
+        &mut GreedyBrain::default(),
+        &[
+            "START 15", "BOARD", "3,3,1", "4,3,1", "5,3,1", "6,3,1", "2,3,2", "1,0,2", "2,0,2",
+            "9,9,2", "DONE",
+        ],
+    );
+
+    assert_eq!(answers[1], "7,3");
+}
+
+#[test]
+fn session_sends_the_message_of_the_brain_before_its_move() {
+    let answers = session(
+        &mut AlphaBetaBrain::default(),
+        &["START 15", "INFO timeout_turn 200", "BEGIN"],
+    );
+
+    assert_eq!(answers.len(), 3);
+    assert!(answers[1].starts_with("MESSAGE depth "));
+    assert!(answers[1].contains(" eval "));
+    location(&answers[2]);
+}
+
+#[test]
+fn greedy_completes_its_four() {
+    let board = board(
+        15,
+        &[(3, 7), (4, 7), (5, 7), (6, 7)],
+        &[(2, 7), (0, 0), (1, 0)],
+    );
+
+    assert_eq!(GreedyBrain::default().play(&board, &quick()), (7, 7).into());
+}
+
+#[test]
+fn alphabeta_wins_rather_than_blocking() {
+    let board = board(
+        15,
+        &[(2, 2), (3, 2), (4, 2), (5, 2)],
+        &[(2, 10), (3, 10), (4, 10), (5, 10), (1, 2)],
+    );
+
+    assert_eq!(
+        AlphaBetaBrain::default().play(&board, &quick()),
+        (6, 2).into()
+    );
+}
+
+#[test]
+fn alphabeta_blocks_four() {
+    let board = board(
+        15,
+        &[(7, 7), (8, 8), (2, 4)],
+        &[(3, 4), (4, 4), (5, 4), (6, 4), (12, 1)],
+    );
+
+    assert_eq!(
+        AlphaBetaBrain::default().play(&board, &quick()),
+        (7, 4).into()
+    );
+}
+
+#[test]
+fn alphabeta_finds_the_forced_win() {
+    // 8,7 makes a four and an open four at once, 8,3 an open four
+    let board = board(
+        15,
+        &[(5, 7), (6, 7), (7, 7), (8, 4), (8, 5), (8, 6)],
+        &[(4, 7), (0, 0), (14, 14), (0, 14), (14, 0), (3, 12)],
+    );
+    let mut brain = AlphaBetaBrain::default();
+    let played = brain.play(&board, &quick());
+
+    assert!([(8, 7), (8, 3)].contains(&(played.x, played.y)));
+    assert!(brain.message().unwrap().contains("eval"));
+}
+
+#[test]
+fn mcts_completes_its_four() {
+    let board = board(
+        15,
+        &[(3, 7), (4, 7), (5, 7), (6, 7)],
+        &[(2, 7), (3, 3), (4, 3), (5, 3)],
+    );
+    let mut brain = MctsBrain::new(MctsSettings {
+        playouts: Some(1000),
+        time_limit: Duration::from_secs(5),
+        ..MctsSettings::default()
+    });
+
+    assert_eq!(brain.play(&board, &BrainSettings::default()), (7, 7).into());
+}
+
+#[test]
+fn brain_executable_speaks_to_the_referee() {
+    let mut ai = Ai::from_spec(env!("CARGO_BIN_EXE_greedy_brain")).unwrap();
+    ai.reset(15);
+    ai.write("BOARD".to_string());
+    for stone in [
+        "3,3,1", "4,3,1", "5,3,1", "6,3,1", "2,3,2", "1,0,2", "2,0,2",
+    ] {
+        ai.write(stone.to_string());
+    }
+    ai.write("DONE".to_string());
+    ai.flush();
+
+    assert_eq!(ai.read_move(), Ok((7, 3).into()));
+    assert!(ai
+        .about(Duration::from_secs(5))
+        .is_some_and(|about| about.contains("greedy")));
+    ai.stop();
+}
@@ -1,34 +1,223 @@
-use crate::runtime::Runtime;
+use std::{
+    io::{self, ErrorKind},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    location::Location,
+    runtime::Runtime,
+    socket::SocketRuntime,
+    trace::{Direction, SharedTrace},
+    transport::Transport,
+};
+
+/// Time an AI has to answer `TAKEBACK`
+pub const TAKEBACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Way an AI failed to give a move
+#[derive(Debug, PartialEq)]
+pub enum Fault {
+    /// Nothing was answered before the turn timeout
+    Timeout,
+    /// The AI stopped answering altogether
+    Crash,
+    /// The answer is not a move
+    Garbage(String),
+}
 
 pub struct Ai {
-    pub runtime: Runtime,
+    pub runtime: Box<dyn Transport>,
+    pub name: String,
+    trace: Option<SharedTrace>,
+    /// Time given for a whole turn, `MESSAGE` and `DEBUG` lines included
+    timeout: Option<Duration>,
+    /// `MESSAGE` and `DEBUG` lines sent since the last `take_messages`
+    messages: Vec<String>,
 }
 
 impl Ai {
-    pub fn new(runtime: Runtime) -> Self {
-        Self { runtime }
+    pub fn new(runtime: Box<dyn Transport>) -> Self {
+        Self {
+            runtime,
+            name: "ai".to_string(),
+            trace: None,
+            timeout: None,
+            messages: Vec::new(),
+        }
     }
 
     pub fn from_path(path: &str) -> Result<Ai, String> {
         let runtime = Runtime::init(path)?;
 
-        Ok(Ai::new(runtime))
+        Ok(Ai::new(Box::new(runtime)).named(path))
+    }
+
+    pub fn from_tcp(address: &str) -> Result<Ai, String> {
+        let runtime = SocketRuntime::connect_tcp(address)?;
+
+        Ok(Ai::new(Box::new(runtime)).named(address))
+    }
+
+    #[cfg(unix)]
+    pub fn from_unix(path: &str) -> Result<Ai, String> {
+        let runtime = SocketRuntime::connect_unix(path)?;
+
+        Ok(Ai::new(Box::new(runtime)).named(path))
+    }
+
+    pub fn named(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Record every line exchanged with this AI into `trace`
+    pub fn set_trace(&mut self, trace: Option<SharedTrace>) {
+        self.trace = trace;
+    }
+
+    fn record(&self, direction: Direction, line: &str) {
+        if let Some(trace) = &self.trace {
+            trace.lock().unwrap().record(direction, &self.name, line);
+        }
+    }
+
+    /// Start an AI from `tcp://<HOST>:<PORT>`, `unix://<SOCKET_PATH>` or an executable path
+    pub fn from_spec(spec: &str) -> Result<Ai, String> {
+        if let Some(address) = spec.strip_prefix("tcp://") {
+            return Ai::from_tcp(address);
+        }
+        if let Some(path) = spec.strip_prefix("unix://") {
+            #[cfg(unix)]
+            return Ai::from_unix(path);
+            #[cfg(not(unix))]
+            return Err(format!("unix sockets are not supported here [{}]", path));
+        }
+        Ai::from_path(spec)
     }
 
     pub fn reset(&mut self, board_size: usize) {
-        match self.runtime.write(format!("START {}", board_size)) {
+        let command = format!("START {}", board_size);
+        self.record(Direction::Send, &command);
+        match self.runtime.write(command) {
             Ok(_) => {}
             Err(err) => {
-                eprintln!("Error whilst reseting board: [{}]", err.to_string());
+                eprintln!("Error whilst reseting board: [{}]", err);
             }
         }
         self.flush();
         let _ = self.read();
     }
 
+    /// Clear the board, keeping the settings of the current game
+    pub fn restart(&mut self) {
+        self.write("RESTART".to_string());
+        self.flush();
+        let _ = self.read();
+    }
+
+    /// Remove the stone at `location` from the board of the AI, waiting `TAKEBACK_TIMEOUT` at most for its `OK`
+    pub fn take_back(&mut self, location: Location) {
+        self.write(format!("TAKEBACK {}", location));
+        self.flush();
+        let deadline = Instant::now() + TAKEBACK_TIMEOUT;
+        match self.read_answer(Some(deadline)) {
+            Ok(answer) if answer.trim_end() == "OK" => {}
+            Ok(answer) => eprintln!(
+                "Error whilst taking back {}: [unexpected answer {}]",
+                location,
+                answer.trim_end()
+            ),
+            Err(err) => eprintln!("Error whilst taking back {}: [{}]", location, err),
+        }
+    }
+
+    /// Answer of the AI to `ABOUT`, such as `name="x", version="1.0"`
+    ///
+    /// The answer is waited for `timeout` at most, whatever the turn timeout.
+    pub fn about(&mut self, timeout: Duration) -> Option<String> {
+        self.write("ABOUT".to_string());
+        self.flush();
+        self.runtime.set_timeout(Some(timeout));
+        let answer = self.runtime.read().ok();
+        self.runtime.set_timeout(self.timeout);
+        let answer = answer?;
+        self.record(Direction::Recv, &answer);
+        let answer = answer.trim_end();
+        (!answer.is_empty()).then(|| answer.to_string())
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+        self.runtime.set_timeout(timeout);
+    }
+
+    /// Read the next move, telling apart the ways the AI can fail to give one
+    ///
+    /// `MESSAGE` and `DEBUG` lines sent before the move are kept for `take_messages`,
+    /// the timeout running for the whole turn rather than for each line.
+    pub fn read_move(&mut self) -> Result<Location, Fault> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let content = match self.read_answer(deadline) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::TimedOut => return Err(Fault::Timeout),
+            Err(err) => {
+                eprintln!("Error whilst reading from the AI: [{}]", err);
+                return Err(Fault::Crash);
+            }
+        };
+
+        if content.is_empty() {
+            return Err(Fault::Crash);
+        }
+
+        let line = content.trim_end();
+        match Location::from_string(line.to_string()) {
+            Ok(location) => Ok(location),
+            Err(_) => Err(Fault::Garbage(line.to_string())),
+        }
+    }
+
+    /// Next line other than `MESSAGE` and `DEBUG`, which are kept for `take_messages`,
+    /// read before `deadline` if any
+    fn read_answer(&mut self, deadline: Option<Instant>) -> io::Result<String> {
+        let answer = loop {
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break Err(io::Error::new(ErrorKind::TimedOut, "no answer in time"));
+                }
+                self.runtime.set_timeout(Some(remaining));
+            }
+            let content = match self.runtime.read() {
+                Ok(content) => content,
+                Err(err) => break Err(err),
+            };
+            self.record(Direction::Recv, &content);
+
+            let line = content.trim_end();
+            if line.starts_with("MESSAGE") || line.starts_with("DEBUG") {
+                self.messages.push(line.to_string());
+                continue;
+            }
+            break Ok(content);
+        };
+        if deadline.is_some() {
+            self.runtime.set_timeout(self.timeout);
+        }
+        answer
+    }
+
+    /// `MESSAGE` and `DEBUG` lines read along with the moves since the last call
+    pub fn take_messages(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.messages)
+    }
+
     pub fn read(&mut self) -> String {
         match self.runtime.read() {
-            Ok(content) => content,
+            Ok(content) => {
+                self.record(Direction::Recv, &content);
+                content
+            }
             Err(err) => {
                 eprintln!("Error whilst writing to the AI: [{}]", err);
                 String::new()
@@ -37,6 +226,7 @@ impl Ai {
     }
 
     pub fn write(&mut self, target: String) {
+        self.record(Direction::Send, &target);
         match self.runtime.write(target) {
             Ok(_) => {}
             Err(err) => {
@@ -57,6 +247,6 @@ impl Ai {
     pub fn stop(&mut self) {
         self.write("END".to_string());
         self.flush();
-        let _ = self.runtime.process.wait();
+        self.runtime.close();
     }
 }
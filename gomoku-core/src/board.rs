@@ -1,6 +1,11 @@
 use std::fmt::{Display, Formatter, Result};
 
-#[derive(Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+use crate::location::{column_name, Location, Notation};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
 pub enum Cell {
     Empty,
     Ai1,
@@ -40,7 +45,7 @@ impl Display for Cell {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Board {
     board: Vec<Vec<Cell>>,
     size: usize,
@@ -48,8 +53,7 @@ pub struct Board {
 
 impl Board {
     pub fn new(size: usize) -> Self {
-        let mut rows: Vec<Vec<Cell>> = Vec::new();
-        rows.reserve(size);
+        let mut rows: Vec<Vec<Cell>> = Vec::with_capacity(size);
         for _ in 0..size {
             let cols = vec![Cell::Empty; size];
             rows.push(cols);
@@ -58,51 +62,86 @@ impl Board {
         Self { board: rows, size }
     }
 
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Cell {
+        self.board[y][x]
+    }
+
+    /// Take back the stone at (x, y)
+    pub fn remove(&mut self, x: usize, y: usize) {
+        self.board[y][x] = Cell::Empty;
+    }
+
+    /// Whether a stone can be placed at (x, y)
+    pub fn is_free(&self, x: usize, y: usize) -> bool {
+        x < self.size && y < self.size && self.board[y][x] == Cell::Empty
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.board
+            .iter()
+            .all(|row| row.iter().all(|cell| *cell != Cell::Empty))
+    }
+
     pub fn place(&mut self, x: usize, y: usize, cell: Cell) {
         if self.board[y][x] != Cell::Empty {
             println!(
                 "ILLEGAL MOVE AT (x,y): ({},{}): ({})",
-                x,
-                y,
-                self.board[y][x]
+                x, y, self.board[y][x]
             );
             println!(
                 "ILLEGAL MOVE AT (x,y): ({},{}): ({})",
-                x,
-                y,
-                self.board[y][x]
+                x, y, self.board[y][x]
             );
             println!(
                 "ILLEGAL MOVE AT (x,y): ({},{}): ({})",
-                x,
-                y,
-                self.board[y][x]
+                x, y, self.board[y][x]
             );
         }
         self.board[y][x] = cell;
     }
 
     pub fn display(&self) {
+        self.display_with(Notation::Protocol);
+    }
+
+    /// Print the board with its columns and rows labelled in `notation`
+    pub fn display_with(&self, notation: Notation) {
+        let (columns, rows): (Vec<String>, Vec<String>) = match notation {
+            Notation::Protocol => (
+                (0..self.size).map(|x| x.to_string()).collect(),
+                (0..self.size).map(|y| y.to_string()).collect(),
+            ),
+            Notation::Algebraic => (
+                (0..self.size).map(column_name).collect(),
+                (0..self.size)
+                    .map(|y| (self.size - y).to_string())
+                    .collect(),
+            ),
+        };
         // Calculate the number of digits in the largest index
         let max_digits = self.size.to_string().len();
 
         // Print column indices
         print!("\t");
-        for i in 0..self.size {
-            let spaces = " ".repeat(max_digits - i.to_string().len() + 1);
-            print!("{}{}", i, spaces);
+        for column in &columns {
+            let spaces = " ".repeat(max_digits - column.len() + 1);
+            print!("{}{}", column, spaces);
         }
-        println!("");
+        println!();
 
         // Print rows
-        for (i, row) in self.board.iter().enumerate() {
-            let spaces = " ".repeat(max_digits - i.to_string().len());
-            print!("{}{}\t", i, spaces);
+        for (row, label) in self.board.iter().zip(&rows) {
+            let spaces = " ".repeat(max_digits - label.len());
+            print!("{}{}\t", label, spaces);
             for cell in row.iter() {
                 let spaces = " ".repeat(max_digits - cell.to_string().len() + 1);
                 print!("{}{}", cell, spaces);
             }
-            println!("");
+            println!();
         }
     }
 
@@ -111,8 +150,8 @@ impl Board {
 
         // Check rows
         for row in &self.board {
-            for i in 0..self.size - self.size + 1 {
-                if &row[i..i + self.size] == pattern {
+            for i in 0..self.size - pattern_size + 1 {
+                if row[i..i + pattern_size] == pattern {
                     return true;
                 }
             }
@@ -125,7 +164,7 @@ impl Board {
                 col_values.push(row[col]);
             }
             for i in 0..self.size - pattern_size + 1 {
-                if &col_values[i..i + pattern_size] == pattern {
+                if col_values[i..i + pattern_size] == pattern {
                     return true;
                 }
             }
@@ -154,7 +193,34 @@ impl Board {
                 }
             }
         }
-        return false;
+        false
+    }
+
+    /// Locations of a five in a row, if any
+    pub fn winning_line(&self) -> Option<Vec<Location>> {
+        let directions: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let cell = self.board[y][x];
+                if cell == Cell::Empty {
+                    continue;
+                }
+                for (dx, dy) in directions {
+                    let line: Vec<Location> = (0..5)
+                        .map_while(|k| {
+                            let x = x.checked_add_signed(dx * k)?;
+                            let y = y.checked_add_signed(dy * k)?;
+                            (x < self.size && y < self.size && self.board[y][x] == cell)
+                                .then_some(Location { x, y })
+                        })
+                        .collect();
+                    if line.len() == 5 {
+                        return Some(line);
+                    }
+                }
+            }
+        }
+        None
     }
 
     pub fn check_win(&self) -> Option<Cell> {
@@ -0,0 +1,35 @@
+use glob::Pattern;
+use regex::Regex;
+
+/// Selects tests by their `<suite>/<test-id>` name
+#[derive(Clone)]
+pub enum Filter {
+    Glob(Pattern),
+    Regex(Regex),
+}
+
+impl Filter {
+    /// A glob pattern, or a regular expression when prefixed with `re:`
+    pub fn new(pattern: &str) -> Result<Filter, String> {
+        match pattern.strip_prefix("re:") {
+            Some(regex) => Regex::new(regex)
+                .map(Filter::Regex)
+                .map_err(|err| format!("invalid regex [{}]: {}", regex, err)),
+            None => Pattern::new(pattern)
+                .map(Filter::Glob)
+                .map_err(|err| format!("invalid glob [{}]: {}", pattern, err)),
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Filter::Glob(pattern) => pattern.matches(name),
+            Filter::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// Name of a test as matched by filters
+pub fn test_name(suite: &str, id: &str) -> String {
+    format!("{}/{}", suite, id)
+}
@@ -1,92 +1,490 @@
-use crate::{ai::Ai, board::*, location::Location};
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    path::Path,
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
 
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::{Ai, Fault},
+    board::*,
+    location::Location,
+    player::{Player, PlayerMove},
+    record::{BrainInfo, GameRecord, RecordSettings, RecordedMove, FREESTYLE},
+    trace::{Direction, Trace},
+};
+
+/// Time the AIs have to answer `ABOUT` once the game is over
+const ABOUT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug)]
 pub struct GameSettings {
     pub board_size: usize,
     pub ai1_starting: bool,
+    /// Time an AI has to answer each move, unlimited if `None`
+    pub timeout_turn: Option<Duration>,
+    /// Path of the protocol trace to write for this game
+    pub trace: Option<String>,
+    /// Path of the game record to write, its extension giving the format
+    pub record: Option<String>,
+    /// Moves played before the AIs take over, starting with the first player's
+    pub opening: Vec<Location>,
+}
+
+impl GameSettings {
+    /// Check every move of the opening is on the board and played once
+    pub fn check_opening(&self) -> Result<(), String> {
+        for (index, location) in self.opening.iter().enumerate() {
+            if location.x >= self.board_size || location.y >= self.board_size {
+                return Err(format!("{} is outside of the board", location));
+            }
+            if self.opening[..index].contains(location) {
+                return Err(format!("{} is played twice", location));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a game ended
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Reason {
+    Five,
+    BoardFull,
+    IllegalMove(Location),
+    Timeout,
+    Crash,
+    InvalidOutput(String),
+    /// The game couldn't start from the opening it was given
+    InvalidOpening(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GameResult {
+    /// `None` on a draw
+    pub winner: Option<Cell>,
+    pub reason: Reason,
+    /// Number of moves played before the end
+    pub moves: usize,
+}
+
+impl GameResult {
+    pub fn new(winner: Option<Cell>, reason: Reason) -> Self {
+        Self {
+            winner,
+            reason,
+            moves: 0,
+        }
+    }
+}
+
+impl Display for GameResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let reason = match &self.reason {
+            Reason::Five => "five in a row".to_string(),
+            Reason::BoardFull => "board is full".to_string(),
+            Reason::IllegalMove(location) => format!("illegal move at {}", location),
+            Reason::Timeout => "opponent timed out".to_string(),
+            Reason::Crash => "opponent crashed".to_string(),
+            Reason::InvalidOutput(content) => format!("invalid output [{}]", content),
+            Reason::InvalidOpening(err) => format!("invalid opening [{}]", err),
+        };
+
+        match self.winner {
+            Some(Cell::Ai1) => write!(f, "Ai 1 has won ({})", reason),
+            Some(_) => write!(f, "Ai 2 has won ({})", reason),
+            None => write!(f, "Draw ({})", reason),
+        }
+    }
+}
+
+/// What happens during a game, for whoever watches it
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    /// New game on an empty board, `first` playing the first move
+    Started {
+        board_size: usize,
+        ai1: String,
+        ai2: String,
+        first: Cell,
+    },
+    /// Stone placed by the opening or by an AI, with the messages it sent
+    Move {
+        cell: Cell,
+        location: Location,
+        time: Duration,
+        messages: Vec<String>,
+    },
+    /// Stone removed on a human's request
+    TakenBack(Location),
+    Ended(GameResult),
 }
 
 pub struct Game {
-    ai1: Ai,
-    ai2: Ai,
+    player1: Player,
+    player2: Player,
+    record: GameRecord,
+    /// AI which hasn't been sent the opening position yet
+    pending_board: Option<Cell>,
+    events: Option<Sender<GameEvent>>,
+}
+
+/// What a turn of the game came to
+enum Turn {
+    Played,
+    /// Moves taken back on a human's request
+    TakenBack(usize),
 }
 
 impl Game {
     pub fn init(path1: &str, path2: &str) -> Result<Self, String> {
-        let ai1 = Ai::from_path(path1)?;
-        let ai2 = Ai::from_path(path2)?;
+        let ai1 = Ai::from_spec(path1)?;
+        let ai2 = Ai::from_spec(path2)?;
+
+        Ok(Self::new(ai1, ai2))
+    }
+
+    pub fn new(ai1: Ai, ai2: Ai) -> Self {
+        Self::with_players(Player::Ai(ai1), Player::Ai(ai2))
+    }
+
+    /// Game where either side may be played by hand
+    pub fn with_players(player1: Player, player2: Player) -> Self {
+        Self {
+            player1,
+            player2,
+            record: GameRecord::new(0, Default::default()),
+            pending_board: None,
+            events: None,
+        }
+    }
+
+    /// Send what happens during the games to `events`
+    pub fn set_events(&mut self, events: Option<Sender<GameEvent>>) {
+        self.events = events;
+    }
+
+    fn emit(&self, event: GameEvent) {
+        if let Some(events) = &self.events {
+            // Nobody watching anymore is no reason to stop the game
+            let _ = events.send(event);
+        }
+    }
 
-        Ok(Self { ai1, ai2 })
+    /// AIs of the game, sides played by hand being left out
+    fn ais(&mut self) -> impl Iterator<Item = &mut Ai> {
+        [&mut self.player1, &mut self.player2]
+            .into_iter()
+            .filter_map(Player::ai)
     }
 
-    fn tick(&mut self, turn: usize, board: &mut Board) {
-        if turn % 2 == 0 {
+    fn tick(&mut self, turn: usize, board: &mut Board) -> Result<Turn, GameResult> {
+        let (cell, opponent_cell, player, opponent) = if turn.is_multiple_of(2) {
             println!("Ai1's turn!");
-            let action = self.ai1.read();
-            match Location::from_string(action) {
-                Ok(location) => {
-                    board.place(location.x, location.y, Cell::Ai1);
-                    Play::Turn(location).process(&mut self.ai2);
-                }
-                Err(err) => {
-                    eprintln!("could not get location: [{}]", err);
-                }
-            }
+            (Cell::Ai1, Cell::Ai2, &mut self.player1, &mut self.player2)
         } else {
             println!("Ai2's turn!");
-            let action = self.ai2.read();
-            match Location::from_string(action) {
-                Ok(location) => {
-                    board.place(location.x, location.y, Cell::Ai2);
-                    Play::Turn(location).process(&mut self.ai1);
+            (Cell::Ai2, Cell::Ai1, &mut self.player2, &mut self.player1)
+        };
+
+        let start = Instant::now();
+        let location = loop {
+            let location = match player.read_move() {
+                Ok(PlayerMove::Play(location)) => location,
+                Ok(PlayerMove::Undo) => {
+                    // Between humans, either can take back the move just played
+                    let count = if opponent.is_human() { 1 } else { 2 };
+                    return Ok(Turn::TakenBack(self.take_back(count, board)));
                 }
-                Err(err) => {
-                    eprintln!("could not get location: [{}]", err);
+                Err(fault) => {
+                    let reason = match fault {
+                        Fault::Timeout => Reason::Timeout,
+                        Fault::Crash => Reason::Crash,
+                        Fault::Garbage(content) => Reason::InvalidOutput(content),
+                    };
+                    return Err(GameResult::new(Some(opponent_cell), reason));
                 }
+            };
+
+            if board.is_free(location.x, location.y) {
+                break location;
+            }
+            // A misclick is no reason to lose
+            if !player.is_human() {
+                return Err(GameResult::new(
+                    Some(opponent_cell),
+                    Reason::IllegalMove(location),
+                ));
+            }
+        };
+
+        board.place(location.x, location.y, cell);
+        let played = RecordedMove::new(location, start.elapsed(), player.take_messages());
+        if let Some(opponent) = opponent.ai() {
+            if self.pending_board == Some(opponent_cell) {
+                self.pending_board = None;
+                Play::Board(stones(board, opponent_cell)).process(opponent);
+            } else {
+                Play::Turn(location).process(opponent);
             }
         }
+        self.emit(GameEvent::Move {
+            cell,
+            location,
+            time: played.time,
+            messages: played.messages.clone(),
+        });
+        self.record.moves.push(played);
         board.display();
+        Ok(Turn::Played)
+    }
+
+    /// Take back the last `count` moves, the opening excepted, telling the AIs
+    fn take_back(&mut self, count: usize, board: &mut Board) -> usize {
+        if self.record.moves.len() < self.record.settings.opening + count {
+            return 0;
+        }
+        for _ in 0..count {
+            let Some(played) = self.record.moves.pop() else {
+                break;
+            };
+            let location = played.location;
+            board.remove(location.x, location.y);
+            for ai in self.ais() {
+                ai.take_back(location);
+            }
+            self.emit(GameEvent::TakenBack(location));
+        }
+        count
+    }
+
+    fn start_trace(&mut self, settings: &GameSettings) {
+        let path = match &settings.trace {
+            Some(path) => path,
+            None => return,
+        };
+
+        let trace = match Trace::create(path) {
+            Ok(trace) => trace.shared(),
+            Err(err) => {
+                eprintln!("{}", err);
+                return;
+            }
+        };
+
+        // Both sides may run the same brain, yet they must be told apart
+        if self.player1.name() == self.player2.name() {
+            self.player1
+                .set_name(format!("{} [1]", self.player1.name()));
+            self.player2
+                .set_name(format!("{} [2]", self.player2.name()));
+        }
+
+        {
+            let mut trace = trace.lock().unwrap();
+            let settings_line = format!(
+                "SETTINGS {} {} {}",
+                settings.board_size,
+                settings.ai1_starting as usize,
+                settings
+                    .timeout_turn
+                    .map_or(0, |timeout| timeout.as_millis())
+            );
+            trace.record(Direction::Meta, "referee", &settings_line);
+            if !settings.opening.is_empty() {
+                let moves: Vec<String> = settings.opening.iter().map(Location::to_string).collect();
+                trace.record(
+                    Direction::Meta,
+                    "referee",
+                    &format!("OPENING {}", moves.join(" ")),
+                );
+            }
+            trace.record(Direction::Meta, self.player1.name(), "PLAYER 1");
+            trace.record(Direction::Meta, self.player2.name(), "PLAYER 2");
+        }
+        for ai in self.ais() {
+            ai.set_trace(Some(trace.clone()));
+        }
+    }
+
+    /// Moves of the last game played, in the order they were played
+    pub fn record(&self) -> &GameRecord {
+        &self.record
     }
 
     /// Run game making AIs fight each other
-    pub fn run(&mut self, settings: &GameSettings) {
+    pub fn run(&mut self, settings: &GameSettings) -> GameResult {
+        if let Err(err) = settings.check_opening() {
+            eprintln!("Error whilst playing opening: [{}]", err);
+            for ai in self.ais() {
+                ai.stop();
+            }
+            let result = GameResult::new(None, Reason::InvalidOpening(err));
+            self.emit(GameEvent::Ended(result.clone()));
+            return result;
+        }
+
         let mut board = Board::new(settings.board_size);
-        let mut turn = 0;
-        self.ai1.reset(settings.board_size);
-        self.ai2.reset(settings.board_size);
+        let mut turn: usize = 0;
+        let specs = [
+            self.player1.name().to_string(),
+            self.player2.name().to_string(),
+        ];
+        self.start_trace(settings);
+        let (first, second) = if settings.ai1_starting {
+            (&self.player1, &self.player2)
+        } else {
+            (&self.player2, &self.player1)
+        };
+        self.record = GameRecord::new(
+            settings.board_size,
+            [first.name().to_string(), second.name().to_string()],
+        );
+        self.record.settings = RecordSettings {
+            rule: FREESTYLE.to_string(),
+            timeout_turn: settings.timeout_turn,
+            opening: settings.opening.len(),
+            ai1_starting: settings.ai1_starting,
+        };
+        for ai in self.ais() {
+            ai.set_timeout(settings.timeout_turn);
+            ai.reset(settings.board_size);
+        }
 
-        if settings.ai1_starting {
-            Play::Begin.process(&mut self.ai1);
+        if let Some(timeout) = settings.timeout_turn {
+            for ai in self.ais() {
+                ai.write(format!("INFO timeout_turn {}", timeout.as_millis()));
+            }
+        }
+        let brains = [
+            brain_info(&specs[0], &mut self.player1),
+            brain_info(&specs[1], &mut self.player2),
+        ];
+        self.record.brains = if settings.ai1_starting {
+            brains
         } else {
+            let [ai1, ai2] = brains;
+            [ai2, ai1]
+        };
+
+        if !settings.ai1_starting {
             turn = 1;
-            Play::Begin.process(&mut self.ai2);
-        }
-
-        loop {
-            match board.check_win() {
-                Some(cell) => {
-                    if cell == Cell::Ai1 {
-                        println!("Ai 1 has won");
-                        break;
-                    } else {
-                        println!("Ai 2 has won");
-                        break;
-                    }
-                }
-                None => {
-                    self.tick(turn, &mut board);
-                    turn += 1;
-                }
+        }
+        self.emit(GameEvent::Started {
+            board_size: settings.board_size,
+            ai1: self.player1.name().to_string(),
+            ai2: self.player2.name().to_string(),
+            first: if settings.ai1_starting {
+                Cell::Ai1
+            } else {
+                Cell::Ai2
+            },
+        });
+        for location in &settings.opening {
+            let cell = if turn.is_multiple_of(2) {
+                Cell::Ai1
+            } else {
+                Cell::Ai2
+            };
+            board.place(location.x, location.y, cell);
+            self.record.push(*location, Duration::ZERO);
+            self.emit(GameEvent::Move {
+                cell,
+                location: *location,
+                time: Duration::ZERO,
+                messages: Vec::new(),
+            });
+            turn += 1;
+        }
+
+        let (cell, player, opponent_cell) = if turn.is_multiple_of(2) {
+            (Cell::Ai1, &mut self.player1, Cell::Ai2)
+        } else {
+            (Cell::Ai2, &mut self.player2, Cell::Ai1)
+        };
+        if self.record.moves.is_empty() {
+            if let Some(ai) = player.ai() {
+                Play::Begin.process(ai);
+            }
+        } else {
+            if let Some(ai) = player.ai() {
+                Play::Board(stones(&board, cell)).process(ai);
+            }
+            self.pending_board = Some(opponent_cell);
+        }
+
+        let first_turn = turn;
+        let mut result = loop {
+            if let Some(cell) = board.check_win() {
+                break GameResult::new(Some(cell), Reason::Five);
+            }
+            if board.is_full() {
+                break GameResult::new(None, Reason::BoardFull);
+            }
+            match self.tick(turn, &mut board) {
+                Ok(Turn::Played) => turn += 1,
+                Ok(Turn::TakenBack(count)) => turn -= count,
+                Err(result) => break result,
+            }
+        };
+        result.moves = turn - first_turn;
+        println!("{}", result);
+
+        // The record counts the winner as the first or second player, whichever Ai started
+        let mut recorded = result.clone();
+        if !settings.ai1_starting {
+            recorded.winner = recorded.winner.map(|cell| match cell {
+                Cell::Ai1 => Cell::Ai2,
+                Cell::Ai2 => Cell::Ai1,
+                other => other,
+            });
+        }
+        self.record.result = Some(recorded);
+        self.emit(GameEvent::Ended(result.clone()));
+
+        if let Some(path) = &settings.record {
+            if let Err(err) = self.record.save(Path::new(path)) {
+                eprintln!("Error whilst saving game record: [{}]", err);
             }
         }
 
-        self.ai1.stop();
-        self.ai2.stop();
+        for ai in self.ais() {
+            ai.stop();
+            ai.set_trace(None);
+        }
+        result
+    }
+}
+
+/// Brain of `player` started from `spec`, as it introduces itself before the game
+fn brain_info(spec: &str, player: &mut Player) -> BrainInfo {
+    let about = player.ai().and_then(|ai| ai.about(ABOUT_TIMEOUT));
+    BrainInfo::new(spec, about.as_deref())
+}
+
+/// Stones of `board` with their owner field as seen by the AI playing `cell`
+fn stones(board: &Board, cell: Cell) -> Vec<(Location, usize)> {
+    let mut stones = Vec::new();
+    for y in 0..board.size() {
+        for x in 0..board.size() {
+            match board.get(x, y) {
+                Cell::Empty => {}
+                stone if stone == cell => stones.push(((x, y).into(), 1)),
+                _ => stones.push(((x, y).into(), 2)),
+            }
+        }
     }
+    stones
 }
 
 pub enum Play {
     Turn(Location),
     Begin,
+    /// Whole position, with the owner field of every stone
+    Board(Vec<(Location, usize)>),
     Invalid,
 }
 
@@ -94,13 +492,21 @@ impl Play {
     pub fn process(&self, ai: &mut Ai) {
         match self {
             Self::Turn(location) => {
-                ai.write(format!("TURN {location}", location = location.to_string()));
+                ai.write(format!("TURN {location}", location = location));
                 ai.flush();
             }
             Self::Begin => {
                 ai.write("BEGIN".to_string());
                 ai.flush();
             }
+            Self::Board(stones) => {
+                ai.write("BOARD".to_string());
+                for (location, owner) in stones {
+                    ai.write(format!("{},{}", location, owner));
+                }
+                ai.write("DONE".to_string());
+                ai.flush();
+            }
             Self::Invalid => {
                 eprintln!("Invalid play !");
             }
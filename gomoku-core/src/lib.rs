@@ -1,6 +1,19 @@
 pub mod ai;
 pub mod board;
+pub mod filter;
 pub mod game;
 pub mod location;
+pub mod mock;
+pub mod player;
+pub mod record;
+pub mod replay;
+pub mod report;
+pub mod rif;
 pub mod runtime;
+pub mod socket;
+pub mod suite;
+pub mod symmetry;
 pub mod test;
+pub mod tournament;
+pub mod trace;
+pub mod transport;
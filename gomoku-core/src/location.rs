@@ -1,4 +1,29 @@
-#[derive(PartialEq)]
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+
+/// Way locations are written
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Notation {
+    /// `x,y` of the protocol, from the top left corner
+    #[default]
+    Protocol,
+    /// `h8` of books and servers, columns being letters from the left and
+    /// rows numbers from the bottom
+    Algebraic,
+}
+
+impl Notation {
+    pub fn from_string(name: &str) -> Result<Notation, String> {
+        match name {
+            "protocol" => Ok(Notation::Protocol),
+            "algebraic" => Ok(Notation::Algebraic),
+            _ => Err(format!("unknown notation [{}]", name)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
 pub struct Location {
     pub x: usize,
     pub y: usize,
@@ -13,13 +38,16 @@ impl From<(usize, usize)> for Location {
     }
 }
 
-impl Location {
-    pub fn to_string(&self) -> String {
-        format!("{x},{y}", x = self.x, y = self.y)
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{x},{y}", x = self.x, y = self.y)
     }
+}
 
+impl Location {
     pub fn from_string(target: String) -> Result<Location, String> {
-        let target = target.trim_end_matches('\n');
+        // Brains may end their lines with `\r\n` as well as `\n`
+        let target = target.trim_end();
         let coords: Vec<&str> = target.split(',').collect();
 
         if coords.len() != 2 {
@@ -42,4 +70,67 @@ impl Location {
 
         Ok(Location { x, y })
     }
+
+    /// Algebraic location such as `h8` on a board of `board_size`, columns past `z` being `aa`, `ab`...
+    pub fn from_algebraic(target: &str, board_size: usize) -> Result<Location, String> {
+        let invalid = || format!("invalid move [{}]", target);
+        let target = target.trim();
+        let split = target
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(target.len());
+        let (column, row) = target.split_at(split);
+        if column.is_empty() {
+            return Err(invalid());
+        }
+        let row: usize = row.parse().map_err(|_| invalid())?;
+        let x = column.bytes().fold(0usize, |x, letter| {
+            x.saturating_mul(26)
+                .saturating_add((letter.to_ascii_lowercase() - b'a') as usize + 1)
+        }) - 1;
+        if x >= board_size || row == 0 || row > board_size {
+            return Err(invalid());
+        }
+        Ok(Location {
+            x,
+            y: board_size - row,
+        })
+    }
+
+    /// Location written either as `x,y` or as `h8`
+    pub fn parse(target: &str, board_size: usize) -> Result<Location, String> {
+        if target.contains(',') {
+            Location::from_string(target.to_string())
+        } else {
+            Location::from_algebraic(target, board_size)
+        }
+    }
+
+    /// `h8`, the location being on a board of `board_size`
+    pub fn to_algebraic(&self, board_size: usize) -> String {
+        format!(
+            "{}{}",
+            column_name(self.x),
+            board_size.saturating_sub(self.y)
+        )
+    }
+
+    pub fn to_notation(&self, notation: Notation, board_size: usize) -> String {
+        match notation {
+            Notation::Protocol => self.to_string(),
+            Notation::Algebraic => self.to_algebraic(board_size),
+        }
+    }
+}
+
+/// Letters of the `x`-th column in algebraic notation: `a` to `z`, then `aa`, `ab`...
+pub fn column_name(x: usize) -> String {
+    let mut name = Vec::new();
+    let mut rest = x + 1;
+    while rest > 0 {
+        rest -= 1;
+        name.push(b'a' + (rest % 26) as u8);
+        rest /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
 }
@@ -0,0 +1,186 @@
+use std::{
+    collections::VecDeque,
+    io::{Error, ErrorKind},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{location::Location, transport::Transport};
+
+/// Answer of mock brains to `ABOUT`
+pub const MOCK_ABOUT: &str = "name=\"mock\", version=\"1.0\"";
+
+/// What a mock brain does once its script is over
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Misbehaviour {
+    /// Never answer again
+    Hang,
+    /// Close its output, like a process that exited
+    Crash,
+    /// Play on the last move it was told about, or outside of the board
+    IllegalMove,
+    /// Answer something that is not a move
+    Garbage,
+}
+
+/// In-process brain for testing the referee without brain executables
+///
+/// It answers `OK` to `START`, `RESTART` and `TAKEBACK`, introduces itself to `ABOUT`, and plays the next move of its
+/// script whenever a move is requested (`BEGIN`, `TURN` or `DONE`).
+pub struct MockBrain {
+    script: VecDeque<Location>,
+    then: Misbehaviour,
+    replies: VecDeque<String>,
+    last_seen: Option<Location>,
+    crashed: bool,
+    message: Option<String>,
+    /// Moves still to be answered late, and the late answer on its way
+    late: usize,
+    delayed: Option<String>,
+    /// Time taken to send each line, and the read timeout it is held to
+    slow: Option<Duration>,
+    timeout: Option<Duration>,
+    received: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockBrain {
+    /// Brain playing `script` then hanging
+    pub fn new(script: Vec<Location>) -> Self {
+        Self {
+            script: script.into(),
+            then: Misbehaviour::Hang,
+            replies: VecDeque::new(),
+            last_seen: None,
+            crashed: false,
+            message: None,
+            late: 0,
+            delayed: None,
+            slow: None,
+            timeout: None,
+            received: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Brain misbehaving from its very first move
+    pub fn misbehaving(misbehaviour: Misbehaviour) -> Self {
+        Self::new(Vec::new()).then(misbehaviour)
+    }
+
+    pub fn then(mut self, misbehaviour: Misbehaviour) -> Self {
+        self.then = misbehaviour;
+        self
+    }
+
+    /// Send `MESSAGE <message>` before each move of the script
+    pub fn saying(mut self, message: &str) -> Self {
+        self.message = Some(message.to_string());
+        self
+    }
+
+    /// Answer the first `count` moves of the script only once the referee gave up waiting for them
+    pub fn late(mut self, count: usize) -> Self {
+        self.late = count;
+        self
+    }
+
+    /// Take `delay` to send each line, timing out like a real brain when reads are given less
+    pub fn slow(mut self, delay: Duration) -> Self {
+        self.slow = Some(delay);
+        self
+    }
+
+    /// Every line the referee sent to this brain
+    pub fn received(&self) -> Arc<Mutex<Vec<String>>> {
+        self.received.clone()
+    }
+
+    fn play(&mut self) {
+        if let Some(location) = self.script.pop_front() {
+            if let Some(message) = &self.message {
+                self.replies.push_back(format!("MESSAGE {}", message));
+            }
+            if self.late > 0 {
+                self.late -= 1;
+                self.delayed = Some(location.to_string());
+            } else {
+                self.replies.push_back(location.to_string());
+            }
+            return;
+        }
+
+        match self.then {
+            Misbehaviour::Hang => {}
+            Misbehaviour::Crash => self.crashed = true,
+            Misbehaviour::IllegalMove => {
+                let location = self.last_seen.unwrap_or((usize::MAX, usize::MAX).into());
+                self.replies.push_back(location.to_string());
+            }
+            Misbehaviour::Garbage => self.replies.push_back("I am not a move".to_string()),
+        }
+    }
+}
+
+impl Transport for MockBrain {
+    fn read(&mut self) -> Result<String, Error> {
+        if let (Some(delay), false) = (self.slow, self.replies.is_empty()) {
+            match self.timeout {
+                Some(timeout) if timeout < delay => {
+                    thread::sleep(timeout);
+                    return Err(Error::new(ErrorKind::TimedOut, "mock brain is slow"));
+                }
+                _ => thread::sleep(delay),
+            }
+        }
+        if let Some(reply) = self.replies.pop_front() {
+            return Ok(format!("{}\n", reply));
+        }
+        if self.crashed {
+            return Ok(String::new());
+        }
+        // The late answer comes in right after this read times out
+        if let Some(reply) = self.delayed.take() {
+            self.replies.push_back(reply);
+            return Err(Error::new(
+                ErrorKind::TimedOut,
+                "mock brain is answering late",
+            ));
+        }
+        // Nothing will ever come, so waiting for the timeout is pointless
+        Err(Error::new(ErrorKind::TimedOut, "mock brain is hanging"))
+    }
+
+    fn write(&mut self, target: String) -> Result<(), Error> {
+        if self.crashed {
+            return Err(Error::new(ErrorKind::BrokenPipe, "mock brain crashed"));
+        }
+        self.received.lock().unwrap().push(target.clone());
+
+        let mut words = target.split_whitespace();
+        match words.next() {
+            Some("START") | Some("RESTART") | Some("TAKEBACK") => {
+                self.replies.push_back("OK".to_string())
+            }
+            Some("ABOUT") => self.replies.push_back(MOCK_ABOUT.to_string()),
+            Some("BEGIN") | Some("DONE") => self.play(),
+            Some("TURN") => {
+                self.last_seen = words
+                    .next()
+                    .and_then(|coords| Location::from_string(coords.to_string()).ok());
+                self.play();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    fn close(&mut self) {}
+}
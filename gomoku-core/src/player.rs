@@ -0,0 +1,83 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::{
+    ai::{Ai, Fault},
+    location::Location,
+};
+
+/// Spec of the side played by hand
+pub const HUMAN: &str = "human";
+
+/// Move of a player, humans being able to take theirs back
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PlayerMove {
+    Play(Location),
+    /// Take back the last move of this player and the moves played since,
+    /// or only the last move when both sides are played by hand
+    Undo,
+}
+
+/// Human giving moves through a channel, from a UI for instance
+pub struct Human {
+    pub name: String,
+    moves: Receiver<PlayerMove>,
+}
+
+impl Human {
+    /// Human player, with the sender of its moves
+    pub fn new(name: &str) -> (Human, Sender<PlayerMove>) {
+        let (sender, moves) = mpsc::channel();
+        let human = Human {
+            name: name.to_string(),
+            moves,
+        };
+        (human, sender)
+    }
+}
+
+/// Side of a game
+pub enum Player {
+    Ai(Ai),
+    Human(Human),
+}
+
+impl Player {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Ai(ai) => &ai.name,
+            Self::Human(human) => &human.name,
+        }
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        match self {
+            Self::Ai(ai) => ai.name = name,
+            Self::Human(human) => human.name = name,
+        }
+    }
+
+    /// AI of this side, none if it is played by hand
+    pub fn ai(&mut self) -> Option<&mut Ai> {
+        match self {
+            Self::Ai(ai) => Some(ai),
+            Self::Human(_) => None,
+        }
+    }
+
+    pub fn is_human(&self) -> bool {
+        matches!(self, Self::Human(_))
+    }
+
+    /// Next move, a human leaving the game counting as a crash
+    pub fn read_move(&mut self) -> Result<PlayerMove, Fault> {
+        match self {
+            Self::Ai(ai) => ai.read_move().map(PlayerMove::Play),
+            Self::Human(human) => human.moves.recv().map_err(|_| Fault::Crash),
+        }
+    }
+
+    /// `MESSAGE` and `DEBUG` lines sent along with the last move
+    pub fn take_messages(&mut self) -> Vec<String> {
+        self.ai().map(Ai::take_messages).unwrap_or_default()
+    }
+}
@@ -0,0 +1,484 @@
+use std::{fs, path::Path, time::Duration};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    board::{Board, Cell},
+    game::GameResult,
+    location::Location,
+    test::TestCondition,
+};
+
+const PSQ_HEADER: &str = "Piskvorky";
+/// Board size of SGF files without a `SZ` property
+const DEFAULT_SGF_SIZE: usize = 15;
+/// Rule checked by the referee: five or more in a row wins
+pub const FREESTYLE: &str = "freestyle";
+/// Words of a brain message followed by its evaluation of the position
+const EVALUATION_KEYS: [&str; 3] = ["eval", "ev", "score"];
+
+/// Move of a recorded game, with the time the player took to find it
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RecordedMove {
+    pub location: Location,
+    #[serde(rename = "time_ms", with = "millis")]
+    pub time: Duration,
+    /// `MESSAGE` and `DEBUG` lines the player sent along with the move
+    #[serde(default)]
+    pub messages: Vec<String>,
+    /// Evaluation of the position the player gave in its messages
+    #[serde(default)]
+    pub evaluation: Option<i64>,
+}
+
+impl RecordedMove {
+    pub fn new(location: Location, time: Duration, messages: Vec<String>) -> Self {
+        Self {
+            location,
+            time,
+            evaluation: evaluation(&messages),
+            messages,
+        }
+    }
+}
+
+/// Brain which played a recorded game
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct BrainInfo {
+    /// Path or address the brain was started from
+    pub spec: String,
+    /// Name the brain gave in its answer to `ABOUT`
+    pub name: Option<String>,
+    /// Version the brain gave in its answer to `ABOUT`
+    pub version: Option<String>,
+}
+
+impl BrainInfo {
+    /// Brain started from `spec`, which answered `about` to `ABOUT`
+    pub fn new(spec: &str, about: Option<&str>) -> Self {
+        let field = |key: &str| about.and_then(|about| about_field(about, key));
+        Self {
+            spec: spec.to_string(),
+            name: field("name"),
+            version: field("version"),
+        }
+    }
+}
+
+/// Settings a recorded game was played with
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RecordSettings {
+    pub rule: String,
+    /// Time a player had for each move, unlimited if `None`
+    #[serde(rename = "timeout_turn_ms", with = "optional_millis")]
+    pub timeout_turn: Option<Duration>,
+    /// Number of moves of the opening, played before the players took over
+    pub opening: usize,
+    /// Whether the first player was Ai 1 of the game
+    pub ai1_starting: bool,
+}
+
+impl Default for RecordSettings {
+    fn default() -> Self {
+        Self {
+            rule: FREESTYLE.to_string(),
+            timeout_turn: None,
+            opening: 0,
+            ai1_starting: true,
+        }
+    }
+}
+
+/// Moves of a game in the order they were played
+///
+/// The first player's stones are `Cell::Ai1` (black), the second player's `Cell::Ai2`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct GameRecord {
+    pub board_size: usize,
+    /// Names of the first and second player
+    pub players: [String; 2],
+    /// Brains of the first and second player
+    #[serde(default)]
+    pub brains: [BrainInfo; 2],
+    #[serde(default)]
+    pub settings: RecordSettings,
+    pub moves: Vec<RecordedMove>,
+    /// How the game ended, `None` if it is unknown
+    ///
+    /// Its winner is the first (`Cell::Ai1`) or second (`Cell::Ai2`) player, like the stones.
+    #[serde(default)]
+    pub result: Option<GameResult>,
+}
+
+impl GameRecord {
+    pub fn new(board_size: usize, players: [String; 2]) -> Self {
+        Self {
+            board_size,
+            players,
+            brains: Default::default(),
+            settings: RecordSettings::default(),
+            moves: Vec::new(),
+            result: None,
+        }
+    }
+
+    pub fn push(&mut self, location: Location, time: Duration) {
+        self.moves
+            .push(RecordedMove::new(location, time, Vec::new()));
+    }
+
+    /// Color of the stone played by the `index`-th move
+    pub fn cell(index: usize) -> Cell {
+        if index.is_multiple_of(2) {
+            Cell::Ai1
+        } else {
+            Cell::Ai2
+        }
+    }
+
+    /// Position after the first `count` moves
+    pub fn board(&self, count: usize) -> Board {
+        let mut board = Board::new(self.board_size);
+        for (index, played) in self.moves.iter().take(count).enumerate() {
+            board.place(
+                played.location.x,
+                played.location.y,
+                GameRecord::cell(index),
+            );
+        }
+        board
+    }
+
+    /// Test of finding the `count + 1`-th move from the position before it
+    pub fn position(&self, count: usize) -> Option<TestCondition> {
+        let next = self.moves.get(count)?;
+        let board = self.moves[..count]
+            .iter()
+            .enumerate()
+            .map(|(index, played)| (played.location, GameRecord::cell(index)))
+            .collect();
+
+        let mut test = TestCondition::new(board, vec![next.location]);
+        test.id = format!("move-{}", count + 1);
+        test.board_size = self.board_size;
+        test.to_move = GameRecord::cell(count);
+        Some(test)
+    }
+
+    /// Piskvork `.psq` file, coordinates being 1-based there
+    pub fn to_psq(&self) -> String {
+        let mut psq = format!(
+            "{} {size}x{size}, 11:11, 0\n",
+            PSQ_HEADER,
+            size = self.board_size
+        );
+        for played in &self.moves {
+            psq += &format!(
+                "{},{},{}\n",
+                played.location.x + 1,
+                played.location.y + 1,
+                played.time.as_millis()
+            );
+        }
+        for player in &self.players {
+            psq += &format!("{}\n", player);
+        }
+        psq += "-1\n";
+        psq
+    }
+
+    pub fn from_psq(content: &str) -> Result<GameRecord, String> {
+        let mut lines = content.lines().map(str::trim);
+        let header = lines.next().ok_or("empty psq file")?;
+        let size = header
+            .strip_prefix(PSQ_HEADER)
+            .and_then(|rest| rest.trim().split(',').next())
+            .ok_or_else(|| format!("invalid psq header [{}]", header))?;
+        let (width, height) = size
+            .split_once('x')
+            .ok_or_else(|| format!("invalid psq board size [{}]", size))?;
+        let board_size: usize = width
+            .parse()
+            .map_err(|_| format!("invalid psq board size [{}]", size))?;
+        if height != width {
+            return Err(format!("only square boards are supported [{}]", size));
+        }
+
+        let mut record = GameRecord::new(board_size, Default::default());
+        let mut trailer = Vec::new();
+        for line in lines.filter(|line| !line.is_empty()) {
+            let fields: Vec<&str> = line.split(',').collect();
+            let numbers: Option<Vec<u64>> = fields.iter().map(|field| field.parse().ok()).collect();
+            match numbers.as_deref() {
+                Some([x, y, time]) if trailer.is_empty() => {
+                    let (x, y) = (*x as usize, *y as usize);
+                    if x == 0 || y == 0 || x > board_size || y > board_size {
+                        return Err(format!("move outside of the board [{}]", line));
+                    }
+                    record.push((x - 1, y - 1).into(), Duration::from_millis(*time));
+                }
+                _ => trailer.push(line),
+            }
+        }
+
+        // The trailer holds the players, then optional numbers such as `-1`
+        let names: Vec<&str> = trailer
+            .into_iter()
+            .filter(|line| line.parse::<i64>().is_err())
+            .collect();
+        if let [.., first, second] = names.as_slice() {
+            record.players = [first.to_string(), second.to_string()];
+        }
+        record.check_moves()?;
+        Ok(record)
+    }
+
+    /// SGF game tree (`GM[4]`), messages of each move being its comment
+    pub fn to_sgf(&self) -> String {
+        let mut sgf = format!(
+            "(;GM[4]FF[4]CA[UTF-8]AP[gomoku_player]SZ[{}]PB[{}]PW[{}]\n",
+            self.board_size,
+            sgf_escape(&self.players[0]),
+            sgf_escape(&self.players[1])
+        );
+        for (index, played) in self.moves.iter().enumerate() {
+            let color = if index.is_multiple_of(2) { 'B' } else { 'W' };
+            sgf += &format!(";{}[{}]", color, sgf_point(played.location));
+            if !played.messages.is_empty() {
+                sgf += &format!("C[{}]", sgf_escape(&played.messages.join("\n")));
+            }
+            sgf += "\n";
+        }
+        sgf += ")\n";
+        sgf
+    }
+
+    /// Main line of an SGF game tree, variations being ignored
+    pub fn from_sgf(content: &str) -> Result<GameRecord, String> {
+        let start = content.find('(').ok_or("no SGF game tree")?;
+        let mut record = GameRecord::new(DEFAULT_SGF_SIZE, Default::default());
+        let mut chars = content[start + 1..].chars();
+        let mut identifier = String::new();
+        let mut in_identifier = false;
+        let mut node_move: Option<usize> = None;
+
+        while let Some(char) = chars.next() {
+            if char.is_ascii_uppercase() {
+                if !in_identifier {
+                    identifier.clear();
+                    in_identifier = true;
+                }
+                identifier.push(char);
+                continue;
+            }
+            in_identifier = false;
+
+            match char {
+                ';' => node_move = None,
+                // The first variation continues the main line
+                '(' => {}
+                ')' => break,
+                '[' => {
+                    let mut value = String::new();
+                    while let Some(char) = chars.next() {
+                        match char {
+                            '\\' => value.extend(chars.next()),
+                            ']' => break,
+                            _ => value.push(char),
+                        }
+                    }
+                    // Further values of a property keep its identifier
+                    node_move = record.apply_sgf(&identifier, &value, node_move)?;
+                }
+                _ => {}
+            }
+        }
+        record.check_moves()?;
+        Ok(record)
+    }
+
+    /// Apply the SGF property `identifier[value]` of the node whose move is `node_move`
+    fn apply_sgf(
+        &mut self,
+        identifier: &str,
+        value: &str,
+        node_move: Option<usize>,
+    ) -> Result<Option<usize>, String> {
+        match identifier {
+            "SZ" => {
+                let size = value.split(':').next().unwrap_or_default();
+                self.board_size = size
+                    .parse()
+                    .map_err(|_| format!("invalid board size [{}]", value))?;
+            }
+            "PB" => self.players[0] = value.to_string(),
+            "PW" => self.players[1] = value.to_string(),
+            "B" | "W" => {
+                let location = parse_sgf_point(value, self.board_size)?;
+                self.push(location, Duration::ZERO);
+                return Ok(Some(self.moves.len() - 1));
+            }
+            "C" => {
+                if let Some(index) = node_move {
+                    let messages: Vec<String> = value.lines().map(str::to_string).collect();
+                    self.moves[index] = RecordedMove::new(
+                        self.moves[index].location,
+                        self.moves[index].time,
+                        messages,
+                    );
+                }
+            }
+            _ => {}
+        }
+        Ok(node_move)
+    }
+
+    /// Lossless JSON record, with the brains, settings and result of the game
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|err| err.to_string())
+    }
+
+    pub fn from_json(content: &str) -> Result<GameRecord, String> {
+        let record: GameRecord = serde_json::from_str(content).map_err(|err| err.to_string())?;
+        record.check_moves()?;
+        Ok(record)
+    }
+
+    /// Check every move is on the board and on a free intersection, so the record can be replayed
+    pub fn check_moves(&self) -> Result<(), String> {
+        for (index, played) in self.moves.iter().enumerate() {
+            let location = played.location;
+            if location.x >= self.board_size || location.y >= self.board_size {
+                return Err(format!("move outside of the board [{}]", location));
+            }
+            if self.moves[..index]
+                .iter()
+                .any(|previous| previous.location == location)
+            {
+                return Err(format!("move on a stone [{}]", location));
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the record in the format given by the extension of `path`
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = match extension(path).as_str() {
+            "psq" => self.to_psq(),
+            "sgf" => self.to_sgf(),
+            "json" => self.to_json()?,
+            other => return Err(format!("unknown game record format [{}]", other)),
+        };
+        fs::write(path, content)
+            .map_err(|err| format!("failed to write [{}]: {}", path.display(), err))
+    }
+
+    /// Load a record in the format given by the extension of `path`
+    pub fn load(path: &Path) -> Result<GameRecord, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read [{}]: {}", path.display(), err))?;
+        let record = match extension(path).as_str() {
+            "psq" => GameRecord::from_psq(&content),
+            "sgf" => GameRecord::from_sgf(&content),
+            "json" => GameRecord::from_json(&content),
+            other => Err(format!("unknown game record format [{}]", other)),
+        };
+        record.map_err(|err| format!("invalid game record [{}]: {}", path.display(), err))
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn sgf_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+/// SGF coordinates go from `a` to `z`, then from `A` to `Z` on wider boards
+fn sgf_point(location: Location) -> String {
+    let letter = |coord: usize| match coord {
+        0..=25 => (b'a' + coord as u8) as char,
+        _ => (b'A' + (coord - 26) as u8) as char,
+    };
+    format!("{}{}", letter(location.x), letter(location.y))
+}
+
+fn parse_sgf_point(text: &str, board_size: usize) -> Result<Location, String> {
+    let coords: Vec<usize> = text
+        .bytes()
+        .filter_map(|letter| match letter {
+            b'a'..=b'z' => Some((letter - b'a') as usize),
+            b'A'..=b'Z' => Some((letter - b'A') as usize + 26),
+            _ => None,
+        })
+        .collect();
+    match coords.as_slice() {
+        [x, y] if *x < board_size && *y < board_size => Ok((*x, *y).into()),
+        _ => Err(format!("invalid SGF move [{}]", text)),
+    }
+}
+
+/// Evaluation given by the last message such as `depth 6 eval 15` or `ev=-30`
+pub fn evaluation(messages: &[String]) -> Option<i64> {
+    messages.iter().rev().find_map(|message| {
+        let mut words = message
+            .split(|char: char| char.is_whitespace() || char == '=' || char == ':')
+            .filter(|word| !word.is_empty());
+        while let Some(word) = words.next() {
+            if EVALUATION_KEYS.contains(&word.to_lowercase().as_str()) {
+                return words.next()?.parse().ok();
+            }
+        }
+        None
+    })
+}
+
+/// Value of `key` in an `ABOUT` answer such as `name="x", version="1.0"`
+fn about_field(about: &str, key: &str) -> Option<String> {
+    about.split(',').find_map(|field| {
+        let (name, value) = field.split_once('=')?;
+        (name.trim() == key).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+fn to_millis(duration: Duration) -> f64 {
+    duration.as_nanos() as f64 / 1e6
+}
+
+/// Rounded to the nanosecond, so that durations written by `to_millis` are read back exactly
+fn from_millis(millis: f64) -> Duration {
+    Duration::from_nanos((millis.max(0.0) * 1e6).round() as u64)
+}
+
+/// Durations as a number of milliseconds
+mod millis {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(to_millis(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        f64::deserialize(deserializer).map(from_millis)
+    }
+}
+
+mod optional_millis {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        duration.map(to_millis).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<f64>::deserialize(deserializer)?.map(from_millis))
+    }
+}
@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use crate::{board::Board, location::Location, record::GameRecord};
+
+/// Time between two moves of the auto-play by default
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(1000);
+const MIN_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_INTERVAL: Duration = Duration::from_secs(8);
+
+/// Recorded game, stepped through move by move
+pub struct Replay {
+    pub record: GameRecord,
+    /// Number of moves shown
+    pub position: usize,
+    /// Time between two moves of the auto-play
+    pub interval: Duration,
+    pub playing: bool,
+}
+
+impl Replay {
+    pub fn new(record: GameRecord, interval: Duration) -> Self {
+        Self {
+            record,
+            position: 0,
+            interval: interval.clamp(MIN_INTERVAL, MAX_INTERVAL),
+            playing: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.record.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.record.moves.is_empty()
+    }
+
+    pub fn board(&self) -> Board {
+        self.record.board(self.position)
+    }
+
+    /// Moves shown, in the order they were played
+    pub fn moves(&self) -> Vec<Location> {
+        self.record.moves[..self.position]
+            .iter()
+            .map(|played| played.location)
+            .collect()
+    }
+
+    pub fn previous(&mut self) {
+        self.position = self.position.saturating_sub(1);
+        self.playing = false;
+    }
+
+    pub fn next(&mut self) {
+        self.position = (self.position + 1).min(self.len());
+    }
+
+    pub fn start(&mut self) {
+        self.position = 0;
+        self.playing = false;
+    }
+
+    pub fn end(&mut self) {
+        self.position = self.len();
+        self.playing = false;
+    }
+
+    /// Start or pause the auto-play, starting over if the end was reached
+    pub fn toggle_play(&mut self) {
+        if !self.playing && self.position == self.len() {
+            self.position = 0;
+        }
+        self.playing = !self.playing;
+    }
+
+    /// Next move of the auto-play, which stops at the end of the game
+    pub fn tick(&mut self) {
+        if self.playing {
+            self.next();
+            self.playing = self.position < self.len();
+        }
+    }
+
+    pub fn faster(&mut self) {
+        self.interval = (self.interval / 2).max(MIN_INTERVAL);
+    }
+
+    pub fn slower(&mut self) {
+        self.interval = (self.interval * 2).min(MAX_INTERVAL);
+    }
+
+    pub fn status(&self) -> String {
+        let mut status = format!(
+            "Move {}/{} ({}, auto-play every {}ms)",
+            self.position,
+            self.len(),
+            if self.playing { "playing" } else { "paused" },
+            self.interval.as_millis()
+        );
+        if let Some(played) = self
+            .position
+            .checked_sub(1)
+            .map(|index| &self.record.moves[index])
+        {
+            let player = &self.record.players[(self.position - 1) % 2];
+            status += &format!(
+                "\n{} played {} in {}ms",
+                player,
+                played.location.to_algebraic(self.record.board_size),
+                played.time.as_millis()
+            );
+            if let Some(evaluation) = played.evaluation {
+                status += &format!(", eval {}", evaluation);
+            }
+            for message in &played.messages {
+                status += &format!("\n{}", message);
+            }
+        }
+        if self.position == self.len() {
+            if let Some(result) = &self.record.result {
+                status += &format!("\n{}", result);
+            }
+        }
+        status
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::{
+    board::Cell,
+    test::{TestCondition, TestResult},
+};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Passed,
+    Failed,
+    /// Failed by exceeding the time limit
+    Timeout,
+    Error,
+}
+
+/// Tested position, stones by color
+#[derive(Serialize, Debug)]
+pub struct Position {
+    pub board_size: usize,
+    pub black: Vec<String>,
+    pub white: Vec<String>,
+    pub to_move: &'static str,
+}
+
+/// Outcome of a single test
+#[derive(Serialize, Debug)]
+pub struct TestReport {
+    pub suite: String,
+    pub id: String,
+    pub position: Position,
+    pub expected: Vec<String>,
+    pub forbidden: Vec<String>,
+    /// Move played by the AI, none if it didn't answer with one
+    pub actual: Option<String>,
+    pub status: Status,
+    pub score: u32,
+    pub max_score: u32,
+    /// Response time of the AI
+    pub time_ms: f64,
+}
+
+/// Every test of a run, in the order they were played
+#[derive(Serialize, Default, Debug)]
+pub struct Report {
+    pub tests: Vec<TestReport>,
+}
+
+/// Response times differing by less than this ratio are considered equal
+const SPEED_RATIO: f64 = 1.5;
+/// Response times differing by less than this are considered equal, in milliseconds
+const SPEED_MIN_DIFF_MS: f64 = 10.0;
+
+/// Test played by two builds of an AI, with its outcome for each of them
+pub struct Change<'a> {
+    pub old: &'a TestReport,
+    pub new: &'a TestReport,
+}
+
+/// Differences between the reports of two builds of an AI on the same tests
+#[derive(Default)]
+pub struct Comparison<'a> {
+    /// Passed with the old build, not with the new one
+    pub regressions: Vec<Change<'a>>,
+    /// Failed with the old build, passed with the new one
+    pub fixes: Vec<Change<'a>>,
+    pub slower: Vec<Change<'a>>,
+    pub faster: Vec<Change<'a>>,
+    /// Tests only played by one of the builds
+    pub unmatched: Vec<&'a TestReport>,
+}
+
+impl Comparison<'_> {
+    pub fn has_regressions(&self) -> bool {
+        !self.regressions.is_empty()
+    }
+
+    pub fn display(&self) {
+        let sections = [
+            ("NEWLY FAILING", &self.regressions),
+            ("NEWLY PASSING", &self.fixes),
+            ("SLOWER", &self.slower),
+            ("FASTER", &self.faster),
+        ];
+        for (title, changes) in sections {
+            println!("{} ({}):", title, changes.len());
+            for change in changes {
+                println!(
+                    "\t{}_test-{}: {} {:.0}ms -> {} {:.0}ms",
+                    change.new.suite,
+                    change.new.id,
+                    change.old.outcome(),
+                    change.old.time_ms,
+                    change.new.outcome(),
+                    change.new.time_ms
+                );
+            }
+        }
+        if !self.unmatched.is_empty() {
+            println!("UNMATCHED ({}):", self.unmatched.len());
+            for test in &self.unmatched {
+                println!("\t{}_test-{}", test.suite, test.id);
+            }
+        }
+    }
+}
+
+fn color(cell: Cell) -> &'static str {
+    match cell {
+        Cell::Ai2 => "white",
+        _ => "black",
+    }
+}
+
+impl TestReport {
+    /// Status, with the move played if any
+    pub fn outcome(&self) -> String {
+        let status = format!("{:?}", self.status).to_lowercase();
+        match &self.actual {
+            Some(actual) => format!("{} ({})", status, actual),
+            None => status,
+        }
+    }
+
+    pub fn new(suite: &str, test: &TestCondition, result: &TestResult, time: Duration) -> Self {
+        let stones = |wanted: Cell| {
+            test.board()
+                .iter()
+                .filter(|(_, cell)| *cell == wanted)
+                .map(|(location, _)| location.to_string())
+                .collect()
+        };
+        let (actual, status) = match result {
+            TestResult::Success(location) => (Some(location.to_string()), Status::Passed),
+            TestResult::Fail(location, _) => (Some(location.to_string()), Status::Failed),
+            TestResult::Timeout(location) => (
+                location.map(|location| location.to_string()),
+                Status::Timeout,
+            ),
+            TestResult::Error => (None, Status::Error),
+        };
+
+        Self {
+            suite: suite.to_string(),
+            id: test.id.clone(),
+            position: Position {
+                board_size: test.board_size,
+                black: stones(Cell::Ai1),
+                white: stones(Cell::Ai2),
+                to_move: color(test.to_move),
+            },
+            expected: test
+                .expected_moves()
+                .iter()
+                .map(|location| location.to_string())
+                .collect(),
+            forbidden: test
+                .forbidden_moves
+                .iter()
+                .map(|location| location.to_string())
+                .collect(),
+            actual,
+            status,
+            score: test.score(result),
+            max_score: test.max_score(),
+            time_ms: time.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn seconds(time_ms: f64) -> String {
+    format!("{:.3}", time_ms / 1000.0)
+}
+
+impl Report {
+    pub fn push(&mut self, test: TestReport) {
+        self.tests.push(test);
+    }
+
+    fn find(&self, suite: &str, id: &str) -> Option<&TestReport> {
+        self.tests
+            .iter()
+            .find(|test| test.suite == suite && test.id == id)
+    }
+
+    /// What changed from `old` to this report, tests being matched by suite and id
+    pub fn compare<'a>(&'a self, old: &'a Report) -> Comparison<'a> {
+        let mut comparison = Comparison::default();
+        for new in &self.tests {
+            let Some(old) = old.find(&new.suite, &new.id) else {
+                comparison.unmatched.push(new);
+                continue;
+            };
+            let passed = |test: &TestReport| test.status == Status::Passed;
+            let change = || Change { old, new };
+            match (passed(old), passed(new)) {
+                (true, false) => comparison.regressions.push(change()),
+                (false, true) => comparison.fixes.push(change()),
+                _ => {}
+            }
+
+            let (fast, slow) = if old.time_ms < new.time_ms {
+                (old.time_ms, new.time_ms)
+            } else {
+                (new.time_ms, old.time_ms)
+            };
+            if slow - fast >= SPEED_MIN_DIFF_MS && slow >= fast * SPEED_RATIO {
+                if new.time_ms > old.time_ms {
+                    comparison.slower.push(change());
+                } else {
+                    comparison.faster.push(change());
+                }
+            }
+        }
+        comparison.unmatched.extend(
+            old.tests
+                .iter()
+                .filter(|test| self.find(&test.suite, &test.id).is_none()),
+        );
+        comparison
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|err| err.to_string())
+    }
+
+    /// JUnit XML, one `testsuite` per suite
+    pub fn to_junit(&self) -> String {
+        let mut suites: Vec<&str> = Vec::new();
+        for test in &self.tests {
+            if !suites.contains(&test.suite.as_str()) {
+                suites.push(&test.suite);
+            }
+        }
+
+        let count = |tests: &[&TestReport], statuses: &[Status]| {
+            tests
+                .iter()
+                .filter(|test| statuses.contains(&test.status))
+                .count()
+        };
+        let failures = [Status::Failed, Status::Timeout];
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let all: Vec<&TestReport> = self.tests.iter().collect();
+        xml += &format!(
+            "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\">\n",
+            all.len(),
+            count(&all, &failures),
+            count(&all, &[Status::Error]),
+            seconds(all.iter().map(|test| test.time_ms).sum()),
+        );
+        for suite in suites {
+            let tests: Vec<&TestReport> = self
+                .tests
+                .iter()
+                .filter(|test| test.suite == suite)
+                .collect();
+            xml += &format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\">\n",
+                escape(suite),
+                tests.len(),
+                count(&tests, &failures),
+                count(&tests, &[Status::Error]),
+                seconds(tests.iter().map(|test| test.time_ms).sum()),
+            );
+            for test in tests {
+                xml += &format!(
+                    "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\"",
+                    escape(suite),
+                    escape(&test.id),
+                    seconds(test.time_ms),
+                );
+                let message = format!(
+                    "played {}, expected [{}], forbidden [{}]",
+                    test.actual.as_deref().unwrap_or("nothing"),
+                    test.expected.join(" "),
+                    test.forbidden.join(" "),
+                );
+                match test.status {
+                    Status::Passed => xml += "/>\n",
+                    Status::Timeout => {
+                        xml += &format!(
+                            ">\n      <failure type=\"timeout\" message=\"timeout, {}\"/>\n    </testcase>\n",
+                            escape(&message)
+                        )
+                    }
+                    Status::Failed => {
+                        xml += &format!(
+                            ">\n      <failure message=\"{}\"/>\n    </testcase>\n",
+                            escape(&message)
+                        )
+                    }
+                    Status::Error => {
+                        xml += &format!(
+                            ">\n      <error message=\"{}\"/>\n    </testcase>\n",
+                            escape(&message)
+                        )
+                    }
+                }
+            }
+            xml += "  </testsuite>\n";
+        }
+        xml += "</testsuites>\n";
+        xml
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_json()?).map_err(|err| err.to_string())
+    }
+
+    pub fn write_junit(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_junit()).map_err(|err| err.to_string())
+    }
+}
+
+/// `path` with `-<suffix>` before its extension, as `report-old.json` or `games-3.psq`
+pub fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, suffix, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, suffix),
+    };
+    path.with_file_name(name)
+}
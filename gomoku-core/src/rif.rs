@@ -0,0 +1,140 @@
+use std::{collections::HashMap, fs, path::Path, time::Duration};
+
+use roxmltree::{Document, Node};
+
+use crate::{location::Location, record::GameRecord};
+
+/// Board size of every RenjuNet game
+pub const RIF_BOARD_SIZE: usize = 15;
+
+/// Named opening of a RIF database
+#[derive(Clone, PartialEq, Debug)]
+pub struct RifOpening {
+    pub id: String,
+    pub name: String,
+    pub moves: Vec<Location>,
+}
+
+/// Game of a RIF database
+#[derive(Clone, PartialEq, Debug)]
+pub struct RifGame {
+    pub id: String,
+    pub black: String,
+    pub white: String,
+    /// Black's score: 1 for a win, 0.5 for a draw, 0 for a loss
+    pub black_result: Option<f32>,
+    /// Id of the opening the game started from
+    pub opening: Option<String>,
+    pub moves: Vec<Location>,
+}
+
+impl RifGame {
+    pub fn to_record(&self) -> GameRecord {
+        let mut record = GameRecord::new(RIF_BOARD_SIZE, [self.black.clone(), self.white.clone()]);
+        for location in &self.moves {
+            record.push(*location, Duration::ZERO);
+        }
+        record
+    }
+}
+
+/// RenjuNet database, as exported in its RIF XML format
+#[derive(Default, Debug)]
+pub struct RifDatabase {
+    pub openings: Vec<RifOpening>,
+    pub games: Vec<RifGame>,
+}
+
+/// RIF move such as `h8`, columns being letters from the left and
+/// rows numbers from the bottom
+pub fn parse_move(text: &str) -> Result<Location, String> {
+    Location::from_algebraic(text, RIF_BOARD_SIZE)
+}
+
+fn parse_moves(text: &str) -> Result<Vec<Location>, String> {
+    let mut moves: Vec<Location> = Vec::new();
+    for text in text.split_whitespace() {
+        let location = parse_move(text)?;
+        if moves.contains(&location) {
+            return Err(format!("{} is played twice", text));
+        }
+        moves.push(location);
+    }
+    Ok(moves)
+}
+
+/// Moves of an element, given either as its text or in a `move` child
+fn element_moves(node: Node) -> Result<Vec<Location>, String> {
+    let text = node
+        .children()
+        .find(|child| child.has_tag_name("move"))
+        .unwrap_or(node)
+        .text()
+        .unwrap_or_default();
+    parse_moves(text)
+}
+
+fn attribute(node: Node, name: &str) -> String {
+    node.attribute(name).unwrap_or_default().to_string()
+}
+
+impl RifDatabase {
+    pub fn parse(content: &str) -> Result<RifDatabase, String> {
+        let document = Document::parse(content).map_err(|err| err.to_string())?;
+        let root = document.root_element();
+
+        let mut players = HashMap::new();
+        for player in root
+            .descendants()
+            .filter(|node| node.has_tag_name("player"))
+        {
+            let name = format!(
+                "{} {}",
+                attribute(player, "name"),
+                attribute(player, "surname")
+            );
+            players.insert(attribute(player, "id"), name.trim().to_string());
+        }
+        let player = |id: String| players.get(&id).cloned().unwrap_or(id);
+
+        let mut database = RifDatabase::default();
+        for opening in root
+            .descendants()
+            .filter(|node| node.has_tag_name("opening"))
+        {
+            let id = attribute(opening, "id");
+            database.openings.push(RifOpening {
+                moves: element_moves(opening).map_err(|err| format!("opening {}: {}", id, err))?,
+                name: attribute(opening, "name"),
+                id,
+            });
+        }
+        for game in root.descendants().filter(|node| node.has_tag_name("game")) {
+            let id = attribute(game, "id");
+            database.games.push(RifGame {
+                moves: element_moves(game).map_err(|err| format!("game {}: {}", id, err))?,
+                black: player(attribute(game, "black")),
+                white: player(attribute(game, "white")),
+                black_result: game
+                    .attribute("bresult")
+                    .and_then(|result| result.parse().ok()),
+                opening: game.attribute("opening").map(str::to_string),
+                id,
+            });
+        }
+        Ok(database)
+    }
+
+    pub fn load(path: &Path) -> Result<RifDatabase, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read [{}]: {}", path.display(), err))?;
+        RifDatabase::parse(&content)
+            .map_err(|err| format!("invalid RIF database [{}]: {}", path.display(), err))
+    }
+
+    pub fn opening(&self, name: &str) -> Option<&RifOpening> {
+        self.openings
+            .iter()
+            .find(|opening| opening.name.eq_ignore_ascii_case(name) || opening.id == name)
+    }
+}
@@ -1,23 +1,34 @@
 use std::{
-    io::{BufRead, BufReader, BufWriter, Error, Write},
-    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
 };
 
+use crate::transport::Transport;
+
+/// Time left to a brain to exit by itself after `END` before being killed
+const EXIT_GRACE: Duration = Duration::from_secs(1);
+
+/// Brain running as a child process, spoken to through its stdin/stdout
 pub struct Runtime {
     pub process: Child,
-    pub stdout: BufReader<ChildStdout>,
     pub stdin: BufWriter<ChildStdin>,
+    /// Lines of the standard output, read on a separate thread so reads can time out
+    lines: Receiver<Result<String, Error>>,
+    timeout: Option<Duration>,
 }
 
 impl Runtime {
     pub fn init(path: &str) -> Result<Self, String> {
-        let mut process = Command::new(&path)
+        let mut process = Command::new(path)
             .stdout(Stdio::piped())
             .stdin(Stdio::piped())
             .spawn()
-            .expect(&format!("failed to start process [{}]", path));
+            .map_err(|err| format!("failed to start process [{}]: {}", path, err))?;
 
-        let stdout = BufReader::new(
+        let mut stdout = BufReader::new(
             process
                 .stdout
                 .take()
@@ -31,27 +42,68 @@ impl Runtime {
                 .expect("failed to capture standard input"),
         );
 
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || loop {
+            let mut content = String::new();
+            let result = stdout.read_line(&mut content).map(|_| content);
+            let done = !matches!(&result, Ok(content) if !content.is_empty());
+
+            if sender.send(result).is_err() || done {
+                break;
+            }
+        });
+
         Ok(Runtime {
             process,
-            stdout,
             stdin,
+            lines,
+            timeout: None,
         })
     }
+}
 
-    pub fn read(&mut self) -> Result<String, Error> {
-        let mut content = String::new();
+impl Transport for Runtime {
+    fn read(&mut self) -> Result<String, Error> {
+        let received = match self.timeout {
+            Some(timeout) => self.lines.recv_timeout(timeout),
+            None => self
+                .lines
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        };
 
-        match self.stdout.read_line(&mut content) {
-            Ok(_) => Ok(content),
-            Err(err) => Err(err),
+        match received {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(Error::new(
+                ErrorKind::TimedOut,
+                "no answer from the process in time",
+            )),
+            // The output was closed, which reads as an empty line like before
+            Err(RecvTimeoutError::Disconnected) => Ok(String::new()),
         }
     }
 
-    pub fn write(&mut self, target: String) -> Result<(), Error> {
+    fn write(&mut self, target: String) -> Result<(), Error> {
         writeln!(self.stdin, "{}", target)
     }
 
-    pub fn flush(&mut self) -> Result<(), Error> {
+    fn flush(&mut self) -> Result<(), Error> {
         self.stdin.flush()
     }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    fn close(&mut self) {
+        let start = Instant::now();
+        while start.elapsed() < EXIT_GRACE {
+            match self.process.try_wait() {
+                Ok(None) => thread::sleep(Duration::from_millis(10)),
+                _ => return,
+            }
+        }
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
 }
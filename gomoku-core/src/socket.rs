@@ -0,0 +1,120 @@
+use std::{
+    io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Read, Write},
+    net::{self, TcpStream},
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+use crate::transport::Transport;
+
+/// Stream a brain server can be reached through
+pub trait Stream: Read + Write + Send + Sized {
+    fn try_clone(&self) -> Result<Self, Error>;
+
+    fn shutdown(&self) -> Result<(), Error>;
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), Error>;
+}
+
+impl Stream for TcpStream {
+    fn try_clone(&self) -> Result<Self, Error> {
+        TcpStream::try_clone(self)
+    }
+
+    fn shutdown(&self) -> Result<(), Error> {
+        TcpStream::shutdown(self, net::Shutdown::Both)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), Error> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+#[cfg(unix)]
+impl Stream for UnixStream {
+    fn try_clone(&self) -> Result<Self, Error> {
+        UnixStream::try_clone(self)
+    }
+
+    fn shutdown(&self) -> Result<(), Error> {
+        UnixStream::shutdown(self, net::Shutdown::Both)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), Error> {
+        UnixStream::set_read_timeout(self, timeout)
+    }
+}
+
+/// Brain running as a server, spoken to through a socket
+pub struct SocketRuntime<S: Stream> {
+    pub reader: BufReader<S>,
+    pub writer: BufWriter<S>,
+}
+
+impl<S: Stream> SocketRuntime<S> {
+    pub fn new(stream: S) -> Result<Self, String> {
+        let reader = match stream.try_clone() {
+            Ok(reader) => BufReader::new(reader),
+            Err(err) => return Err(format!("failed to clone socket: {}", err)),
+        };
+
+        Ok(Self {
+            reader,
+            writer: BufWriter::new(stream),
+        })
+    }
+}
+
+impl SocketRuntime<TcpStream> {
+    pub fn connect_tcp(address: &str) -> Result<Self, String> {
+        match TcpStream::connect(address) {
+            Ok(stream) => Self::new(stream),
+            Err(err) => Err(format!("failed to connect to [{}]: {}", address, err)),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl SocketRuntime<UnixStream> {
+    pub fn connect_unix(path: &str) -> Result<Self, String> {
+        match UnixStream::connect(path) {
+            Ok(stream) => Self::new(stream),
+            Err(err) => Err(format!("failed to connect to [{}]: {}", path, err)),
+        }
+    }
+}
+
+impl<S: Stream> Transport for SocketRuntime<S> {
+    fn read(&mut self) -> Result<String, Error> {
+        let mut content = String::new();
+
+        match self.reader.read_line(&mut content) {
+            Ok(_) => Ok(content),
+            // Depending on the platform, a read timeout is reported either way
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                Err(Error::new(ErrorKind::TimedOut, err))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn write(&mut self, target: String) -> Result<(), Error> {
+        writeln!(self.writer, "{}", target)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        if let Err(err) = self.reader.get_ref().set_read_timeout(timeout) {
+            eprintln!("Error whilst setting socket timeout: [{}]", err);
+        }
+    }
+
+    fn close(&mut self) {
+        let _ = self.writer.get_ref().shutdown();
+    }
+}
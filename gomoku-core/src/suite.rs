@@ -0,0 +1,211 @@
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{
+    board::Cell,
+    location::Location,
+    test::{TestCondition, ACCEPTABLE_SCORE, BEST_SCORE, BLUNDER_SCORE},
+};
+
+const DEFAULT_BOARD_SIZE: usize = 20;
+
+/// Test position as written in a suite file
+///
+/// The tested AI is always the one to move. Stones are given either as
+/// `own` and `opponent` (the tested AI then plays black), or by color as
+/// `black` and `white` with the color to move in `to_move`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestFile {
+    id: Option<String>,
+    #[serde(default)]
+    own: Vec<String>,
+    #[serde(default)]
+    opponent: Vec<String>,
+    #[serde(default)]
+    black: Vec<String>,
+    #[serde(default)]
+    white: Vec<String>,
+    /// `black` or `white`, the side with fewer stones by default (black if even)
+    to_move: Option<String>,
+    /// Stones sent with owner field 3, for brains playing continuous games
+    #[serde(default)]
+    continuous: Vec<String>,
+    /// Moves passing the test, any move not forbidden passes if empty
+    #[serde(default)]
+    expected: Vec<String>,
+    #[serde(default)]
+    forbidden: Vec<String>,
+    /// Points of each listed move, the best ones passing the test if `expected` is empty
+    #[serde(default)]
+    scores: BTreeMap<String, MoveScore>,
+    /// Time limit in milliseconds, sent to the AI as `timeout_turn`
+    time_limit: Option<u64>,
+}
+
+/// Points of a move, or `best`, `acceptable` or `blunder`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MoveScore {
+    Points(u32),
+    Named(String),
+}
+
+impl MoveScore {
+    fn points(&self) -> Result<u32, String> {
+        match self {
+            MoveScore::Points(points) => Ok(*points),
+            MoveScore::Named(name) => match name.as_str() {
+                "best" => Ok(BEST_SCORE),
+                "acceptable" => Ok(ACCEPTABLE_SCORE),
+                "blunder" => Ok(BLUNDER_SCORE),
+                _ => Err(format!("unknown score [{}]", name)),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SuiteFile {
+    name: Option<String>,
+    board_size: Option<usize>,
+    #[serde(default)]
+    tests: Vec<TestFile>,
+}
+
+/// Named set of test positions, loaded from a TOML file
+#[derive(Clone)]
+pub struct Suite {
+    pub name: String,
+    pub board_size: usize,
+    pub tests: Vec<TestCondition>,
+}
+
+/// Location written either as `x,y` or as `h8`, which must be on the board
+fn location(coords: &str, board_size: usize) -> Result<Location, String> {
+    let location = Location::parse(coords, board_size)?;
+    if location.x >= board_size || location.y >= board_size {
+        return Err(format!("{} is outside of the board", coords));
+    }
+    Ok(location)
+}
+
+fn locations(coords: &[String], board_size: usize) -> Result<Vec<Location>, String> {
+    coords
+        .iter()
+        .map(|coords| location(coords, board_size))
+        .collect()
+}
+
+impl Suite {
+    pub fn from_toml(name: &str, content: &str) -> Result<Suite, String> {
+        let file: SuiteFile = toml::from_str(content).map_err(|err| err.to_string())?;
+        let board_size = file.board_size.unwrap_or(DEFAULT_BOARD_SIZE);
+
+        let mut tests = Vec::new();
+        for (index, test) in file.tests.into_iter().enumerate() {
+            let id = test.id.unwrap_or_else(|| index.to_string());
+            let with_id = |err: String| format!("test {}: {}", id, err);
+
+            let by_owner = !test.own.is_empty() || !test.opponent.is_empty();
+            let by_color = !test.black.is_empty() || !test.white.is_empty();
+            if by_owner && (by_color || test.to_move.is_some()) {
+                return Err(with_id(
+                    "own/opponent can't be mixed with black/white/to_move".to_string(),
+                ));
+            }
+
+            let (black, white) = if by_owner {
+                (&test.own, &test.opponent)
+            } else {
+                (&test.black, &test.white)
+            };
+            let mut board = Vec::new();
+            for location in locations(black, board_size).map_err(with_id)? {
+                board.push((location, Cell::Ai1));
+            }
+            for location in locations(white, board_size).map_err(with_id)? {
+                board.push((location, Cell::Ai2));
+            }
+            let to_move = match test.to_move.as_deref() {
+                Some("black") => Cell::Ai1,
+                Some("white") => Cell::Ai2,
+                Some(color) => return Err(with_id(format!("unknown color to move [{}]", color))),
+                None if !by_owner && white.len() < black.len() => Cell::Ai2,
+                None => Cell::Ai1,
+            };
+
+            let continuous = locations(&test.continuous, board_size).map_err(with_id)?;
+            if let Some(location) = continuous
+                .iter()
+                .find(|location| !board.iter().any(|(stone, _)| stone == *location))
+            {
+                return Err(with_id(format!("continuous {} is not a stone", location)));
+            }
+
+            let mut condition = TestCondition::new(
+                board,
+                locations(&test.expected, board_size).map_err(with_id)?,
+            );
+            condition.id = id.clone();
+            condition.to_move = to_move;
+            condition.continuous = continuous;
+            condition.board_size = board_size;
+            condition.forbidden_moves = locations(&test.forbidden, board_size).map_err(with_id)?;
+            for (coords, score) in &test.scores {
+                let location = location(coords, board_size).map_err(with_id)?;
+                condition
+                    .move_scores
+                    .push((location, score.points().map_err(with_id)?));
+            }
+            condition.time_limit = test.time_limit.map(Duration::from_millis);
+            tests.push(condition);
+        }
+
+        Ok(Suite {
+            name: file.name.unwrap_or_else(|| name.to_string()),
+            board_size,
+            tests,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Suite, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read suite [{}]: {}", path.display(), err))?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Suite::from_toml(&name, &content)
+            .map_err(|err| format!("invalid suite [{}]: {}", path.display(), err))
+    }
+
+    /// Load a suite file, or every `.toml` suite found under a directory
+    pub fn load_all(path: &Path) -> Result<Vec<Suite>, String> {
+        if !path.is_dir() {
+            return Ok(vec![Suite::load(path)?]);
+        }
+
+        let mut entries: Vec<_> = fs::read_dir(path)
+            .map_err(|err| format!("failed to read [{}]: {}", path.display(), err))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        entries.sort();
+
+        let mut suites = Vec::new();
+        for entry in entries {
+            if entry.is_dir() {
+                suites.extend(Suite::load_all(&entry)?);
+            } else if entry
+                .extension()
+                .is_some_and(|extension| extension == "toml")
+            {
+                suites.push(Suite::load(&entry)?);
+            }
+        }
+        Ok(suites)
+    }
+}
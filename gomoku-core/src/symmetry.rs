@@ -0,0 +1,103 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::location::Location;
+
+/// Distance a position keeps from the edges to be translated,
+/// so the edge never blocks a line that was open in the original
+const EDGE_MARGIN: usize = 5;
+
+/// One of the 8 symmetries of a square board
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    MirrorX,
+    MirrorY,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::MirrorX,
+        Symmetry::MirrorY,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::Identity => "identity",
+            Symmetry::Rotate90 => "rot90",
+            Symmetry::Rotate180 => "rot180",
+            Symmetry::Rotate270 => "rot270",
+            Symmetry::MirrorX => "mirror-x",
+            Symmetry::MirrorY => "mirror-y",
+            Symmetry::Transpose => "transpose",
+            Symmetry::AntiTranspose => "anti-transpose",
+        }
+    }
+
+    pub fn apply(&self, location: Location, board_size: usize) -> Location {
+        let max = board_size - 1;
+        let (x, y) = (location.x, location.y);
+        let (x, y) = match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (max - y, x),
+            Symmetry::Rotate180 => (max - x, max - y),
+            Symmetry::Rotate270 => (y, max - x),
+            Symmetry::MirrorX => (max - x, y),
+            Symmetry::MirrorY => (x, max - y),
+            Symmetry::Transpose => (y, x),
+            Symmetry::AntiTranspose => (max - y, max - x),
+        };
+        Location { x, y }
+    }
+}
+
+impl Display for Symmetry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Offsets moving `locations` around the board without bringing them
+/// closer than `EDGE_MARGIN` to an edge, the null offset included
+///
+/// Only the extreme and original placements on each axis are kept,
+/// positions already close to an edge are never translated.
+pub fn translations(locations: &[Location], board_size: usize) -> Vec<(isize, isize)> {
+    let axis = |coords: Vec<usize>| -> Vec<isize> {
+        let (Some(&min), Some(&max)) = (coords.iter().min(), coords.iter().max()) else {
+            return vec![0];
+        };
+        if min < EDGE_MARGIN || max + EDGE_MARGIN >= board_size {
+            return vec![0];
+        }
+        let lowest = EDGE_MARGIN as isize - min as isize;
+        let highest = (board_size - 1 - EDGE_MARGIN) as isize - max as isize;
+        let mut offsets = vec![lowest, 0, highest];
+        offsets.dedup();
+        offsets
+    };
+
+    let xs = axis(locations.iter().map(|location| location.x).collect());
+    let ys = axis(locations.iter().map(|location| location.y).collect());
+    xs.iter()
+        .flat_map(|&dx| ys.iter().map(move |&dy| (dx, dy)))
+        .collect()
+}
+
+/// Move `location` by `offset`, which must keep it on the board
+pub fn translate(location: Location, offset: (isize, isize)) -> Location {
+    Location {
+        x: location.x.saturating_add_signed(offset.0),
+        y: location.y.saturating_add_signed(offset.1),
+    }
+}
@@ -1,59 +1,247 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
 use crate::{
-    ai::Ai,
+    ai::{Ai, Fault},
     board::{Board, Cell},
-    location::Location,
+    filter::{test_name, Filter},
+    location::{Location, Notation},
+    report::{Report, TestReport},
+    suite::Suite,
+    symmetry::{self, Symmetry},
 };
 
+#[derive(Clone)]
 pub struct TestCondition {
+    pub id: String,
+    pub board_size: usize,
+    /// Stones by color, `Cell::Ai1` being black (first player) and `Cell::Ai2` white
     board: Vec<(Location, Cell)>,
+    /// Color played by the tested AI
+    pub to_move: Cell,
+    /// Stones sent with the continuous game owner field (3)
+    pub continuous: Vec<Location>,
+    /// Any move not forbidden passes if empty
     expected_moves: Vec<Location>,
+    pub forbidden_moves: Vec<Location>,
+    /// Points given for a move, unlisted moves being worth nothing
+    pub move_scores: Vec<(Location, u32)>,
+    pub time_limit: Option<Duration>,
+    /// Symmetry applied to the position as written in its suite
+    pub orientation: Symmetry,
     result_board: Option<Board>,
 }
 
+#[derive(Debug)]
 pub enum TestResult {
     Success(Location),
     Fail(Location, Board),
+    /// Time limit exceeded, with the move if it came late
+    Timeout(Option<Location>),
     Error,
 }
 
+/// Usual points of the best move of a test
+pub const BEST_SCORE: u32 = 10;
+/// Usual points of a move that is good enough
+pub const ACCEPTABLE_SCORE: u32 = 5;
+/// Points of a losing move
+pub const BLUNDER_SCORE: u32 = 0;
+
+/// Extra time given to the AI to answer past its limit before giving up on the move
+const TIMEOUT_GRACE: Duration = Duration::from_millis(500);
+/// Time a move given up on is still waited for before the next test,
+/// for AIs which can't be started again
+const LATE_ANSWER_TIMEOUT: Duration = Duration::from_secs(5);
+
 impl TestCondition {
     pub fn new(board: Vec<(Location, Cell)>, expected_moves: Vec<Location>) -> Self {
         Self {
+            id: String::new(),
+            board_size: 20,
             board,
+            to_move: Cell::Ai1,
+            continuous: Vec::new(),
             expected_moves,
+            forbidden_moves: Vec::new(),
+            move_scores: Vec::new(),
+            time_limit: None,
+            orientation: Symmetry::Identity,
             result_board: None,
         }
     }
 
+    pub fn board(&self) -> &[(Location, Cell)] {
+        &self.board
+    }
+
+    pub fn expected_moves(&self) -> &[Location] {
+        &self.expected_moves
+    }
+
+    /// Copy of the test with every location mapped by `symmetry`, then moved by `offset`
+    pub fn transformed(&self, symmetry: Symmetry, offset: (isize, isize)) -> TestCondition {
+        let map = |location: &Location| {
+            symmetry::translate(symmetry.apply(*location, self.board_size), offset)
+        };
+
+        let mut test = self.clone();
+        test.id = match offset {
+            (0, 0) => format!("{}@{}", self.id, symmetry),
+            (dx, dy) => format!("{}@{}{:+}{:+}", self.id, symmetry, dx, dy),
+        };
+        test.orientation = symmetry;
+        test.board = self
+            .board
+            .iter()
+            .map(|(location, cell)| (map(location), *cell))
+            .collect();
+        test.continuous = self.continuous.iter().map(map).collect();
+        test.expected_moves = self.expected_moves.iter().map(map).collect();
+        test.forbidden_moves = self.forbidden_moves.iter().map(map).collect();
+        test.move_scores = self
+            .move_scores
+            .iter()
+            .map(|(location, score)| (map(location), *score))
+            .collect();
+        test
+    }
+
+    /// The test in the 8 orientations of the board if `symmetries` is set,
+    /// translated to the extremes of the board if `translations` is set
+    pub fn augmented(&self, symmetries: bool, translations: bool) -> Vec<TestCondition> {
+        let orientations: &[Symmetry] = if symmetries {
+            &Symmetry::ALL
+        } else {
+            &[Symmetry::Identity]
+        };
+        let mut tests = Vec::new();
+        for &symmetry in orientations {
+            let test = self.transformed(symmetry, (0, 0));
+            let offsets = if translations {
+                symmetry::translations(&test.locations(), self.board_size)
+            } else {
+                vec![(0, 0)]
+            };
+            for offset in offsets {
+                tests.push(self.transformed(symmetry, offset));
+            }
+        }
+        tests
+    }
+
+    fn move_score(&self, action: Location) -> u32 {
+        if self.forbidden_moves.contains(&action) {
+            return BLUNDER_SCORE;
+        }
+        self.move_scores
+            .iter()
+            .find(|(location, _)| *location == action)
+            .map_or(BLUNDER_SCORE, |(_, score)| *score)
+    }
+
+    /// Points scored by `result`, tests without move scores giving
+    /// `BEST_SCORE` when passed
+    pub fn score(&self, result: &TestResult) -> u32 {
+        match result {
+            TestResult::Success(_) if self.move_scores.is_empty() => BEST_SCORE,
+            TestResult::Success(action) | TestResult::Fail(action, _)
+                if !self.move_scores.is_empty() =>
+            {
+                self.move_score(*action)
+            }
+            _ => BLUNDER_SCORE,
+        }
+    }
+
+    /// Points scored by the best move
+    pub fn max_score(&self) -> u32 {
+        self.move_scores
+            .iter()
+            .map(|(_, score)| *score)
+            .max()
+            .unwrap_or(BEST_SCORE)
+    }
+
+    fn locations(&self) -> Vec<Location> {
+        self.board
+            .iter()
+            .map(|(location, _)| *location)
+            .chain(self.expected_moves.iter().copied())
+            .chain(self.forbidden_moves.iter().copied())
+            .chain(self.move_scores.iter().map(|(location, _)| *location))
+            .collect()
+    }
+
+    /// Owner field of a stone in the BOARD command, from the tested AI's point of view
+    fn owner(&self, location: Location, cell: Cell) -> usize {
+        if self.continuous.contains(&location) {
+            3
+        } else if cell == self.to_move {
+            1
+        } else {
+            2
+        }
+    }
+
     fn send_board(&mut self, ai: &mut Ai) {
-        let mut board = Board::new(20);
-        ai.reset(20);
+        let mut board = Board::new(self.board_size);
+        // Sent even without a limit, one from a previous test would hold otherwise
+        let timeout_turn = self
+            .time_limit
+            .map_or(0, |time_limit| time_limit.as_millis());
+        ai.write(format!("INFO timeout_turn {}", timeout_turn));
         ai.write("BOARD".to_string());
         for cell in &self.board {
             board.place(cell.0.x, cell.0.y, cell.1);
-            ai.write(format!("{},{}", cell.0.to_string(), cell.1.get_id()));
+            ai.write(format!("{},{}", cell.0, self.owner(cell.0, cell.1)));
         }
         ai.write("DONE".to_string());
         self.result_board = Some(board);
     }
 
-    fn read_move(&mut self, ai: &mut Ai) -> TestResult {
+    /// Read the AI's answer to the position, with its response time
+    fn read_move(&mut self, ai: &mut Ai) -> (TestResult, Duration) {
         ai.flush();
-        let action = Location::from_string(ai.read());
+        let start = Instant::now();
+        let action = ai.read_move();
+        ai.take_messages();
+        let elapsed = start.elapsed();
+        (self.judge(action, elapsed), elapsed)
+    }
+
+    fn judge(&self, action: Result<Location, Fault>, elapsed: Duration) -> TestResult {
+        let late = self
+            .time_limit
+            .is_some_and(|time_limit| elapsed > time_limit);
 
         let action = match action {
+            Ok(action) if late => return TestResult::Timeout(Some(action)),
             Ok(action) => action,
-            Err(err) => {
-                eprintln!("Error whilst converting move: [{}]", err);
+            Err(Fault::Timeout) if self.time_limit.is_some() => return TestResult::Timeout(None),
+            Err(fault) => {
+                eprintln!("Error whilst reading move: [{:?}]", fault);
                 return TestResult::Error;
             }
         };
 
-        let mut result_board = self.result_board.clone().unwrap_or(Board::new(20));
+        let mut result_board = self
+            .result_board
+            .clone()
+            .unwrap_or(Board::new(self.board_size));
 
-        result_board.place(action.x, action.y, Cell::NewAi1);
+        if result_board.is_free(action.x, action.y) {
+            result_board.place(action.x, action.y, Cell::NewAi1);
+        }
 
-        if self.expected_moves.contains(&action) {
+        let expected = if self.expected_moves.is_empty() && !self.move_scores.is_empty() {
+            self.move_score(action) == self.max_score()
+        } else {
+            self.expected_moves.is_empty() || self.expected_moves.contains(&action)
+        };
+        if expected && !self.forbidden_moves.contains(&action) {
             TestResult::Success(action)
         } else {
             TestResult::Fail(action, result_board)
@@ -61,184 +249,337 @@ impl TestCondition {
     }
 }
 
+/// How much of the AI's state is reset between two tests
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Isolation {
+    /// Start a new process for every test
+    Process,
+    /// Send RESTART before every test
+    Restart,
+    /// Send START before every test
+    #[default]
+    Start,
+    /// Only send the position, as if the tests were one long session
+    Continuous,
+}
+
+impl Isolation {
+    pub fn from_string(name: &str) -> Result<Isolation, String> {
+        match name {
+            "process" => Ok(Isolation::Process),
+            "restart" => Ok(Isolation::Restart),
+            "start" => Ok(Isolation::Start),
+            "continuous" => Ok(Isolation::Continuous),
+            _ => Err(format!("unknown isolation [{}]", name)),
+        }
+    }
+}
+
+/// How the tests of a run are expanded, selected, isolated, timed and reported
+#[derive(Clone, Default)]
+pub struct TestOptions {
+    /// Run every test in the 8 orientations of the board
+    pub symmetries: bool,
+    /// Also run every orientation translated across the board, where it stays clear of the edges
+    pub translations: bool,
+    pub isolation: Isolation,
+    /// Only run the tests matching one of these, all of them if empty
+    pub filters: Vec<Filter>,
+    /// Stop at the first test not passed
+    pub fail_fast: bool,
+    /// Print the selected tests instead of running them
+    pub list: bool,
+    /// Time limit of the tests without one of their own
+    pub time_limit: Option<Duration>,
+    /// Write the report of the run as JSON
+    pub json: Option<PathBuf>,
+    /// Write the report of the run as JUnit XML
+    pub junit: Option<PathBuf>,
+    /// Notation of the moves and boards printed for failed tests
+    pub notation: Notation,
+}
+
+/// Outcome of the tests of a suite
+#[derive(Default)]
+struct SuiteScore {
+    name: String,
+    passed: usize,
+    total: usize,
+    points: u32,
+    max_points: u32,
+}
+
+impl SuiteScore {
+    fn add(&mut self, success: bool, points: u32, max_points: u32) {
+        self.passed += success as usize;
+        self.total += 1;
+        self.points += points;
+        self.max_points += max_points;
+    }
+}
+
+/// Tests of `suites` to run with `options`, augmented and filtered,
+/// suites left without tests being dropped
+pub fn select(suites: Vec<Suite>, options: &TestOptions) -> Vec<Suite> {
+    let augment = options.symmetries || options.translations;
+    let mut selected = Vec::new();
+    for mut suite in suites {
+        if augment {
+            suite.tests = suite
+                .tests
+                .iter()
+                .flat_map(|test| test.augmented(options.symmetries, options.translations))
+                .collect();
+        }
+        for (index, test) in suite.tests.iter_mut().enumerate() {
+            if test.id.is_empty() {
+                test.id = index.to_string();
+            }
+            test.time_limit = test.time_limit.or(options.time_limit);
+        }
+        suite.tests.retain(|test| {
+            let name = test_name(&suite.name, &test.id);
+            options.filters.is_empty() || options.filters.iter().any(|filter| filter.matches(&name))
+        });
+        if !suite.tests.is_empty() {
+            selected.push(suite);
+        }
+    }
+    selected
+}
+
 pub struct Test {
     ai: Ai,
+    /// Where the AI was started from, to start it again
+    spec: Option<String>,
+    pub isolation: Isolation,
+    /// Stop at the first test not passed
+    pub fail_fast: bool,
+    /// Notation of the moves and boards printed for failed tests
+    pub notation: Notation,
+    /// Board size of the game the AI is playing, if started
+    started: Option<usize>,
+    /// Whether the AI may still send an answer to a previous test
+    desynchronized: bool,
+    report: Report,
 }
 
 impl Test {
     pub fn init(path: &str) -> Result<Self, String> {
-        let ai = Ai::from_path(path)?;
+        let ai = Ai::from_spec(path)?;
+
+        let mut test = Self::new(ai);
+        test.spec = Some(path.to_string());
+        Ok(test)
+    }
+
+    pub fn new(ai: Ai) -> Self {
+        Self {
+            ai,
+            spec: None,
+            isolation: Isolation::default(),
+            fail_fast: false,
+            notation: Notation::default(),
+            started: None,
+            desynchronized: false,
+            report: Report::default(),
+        }
+    }
+
+    /// Bring the AI to an empty board of `board_size`, as fresh as the isolation asks
+    ///
+    /// An AI which failed to answer the previous test is started again,
+    /// or waited for if it can't be, so its late answer isn't taken for the next one.
+    fn prepare(&mut self, board_size: usize, timeout: Option<Duration>) -> Result<(), String> {
+        let start_again = match self.isolation {
+            Isolation::Process => self.started.is_some(),
+            _ => self.desynchronized && self.spec.is_some(),
+        };
+        if start_again {
+            self.start_again()?;
+        } else if self.desynchronized {
+            self.wait_late_answer()?;
+        }
+
+        let same_game = self.started == Some(board_size) && !self.desynchronized;
+        self.ai.set_timeout(timeout);
+        match self.isolation {
+            Isolation::Restart if same_game => self.ai.restart(),
+            Isolation::Continuous if same_game => {}
+            _ => self.ai.reset(board_size),
+        }
+        self.started = Some(board_size);
+        self.desynchronized = false;
+        Ok(())
+    }
+
+    /// Replace the AI by a new one started from the same spec, keeping it if that fails
+    fn start_again(&mut self) -> Result<(), String> {
+        let Some(spec) = &self.spec else {
+            return Err("the AI can't be started again".to_string());
+        };
+        let ai = Ai::from_spec(spec)?;
+        self.ai.stop();
+        self.ai = ai;
+        self.started = None;
+        Ok(())
+    }
 
-        Ok(Self { ai })
+    /// Read the answer the AI still owes to the previous test
+    fn wait_late_answer(&mut self) -> Result<(), String> {
+        self.ai.set_timeout(Some(LATE_ANSWER_TIMEOUT));
+        let answer = self.ai.read_move();
+        self.ai.take_messages();
+        match answer {
+            Err(Fault::Timeout) => Err("the AI never answered the previous test".to_string()),
+            _ => Ok(()),
+        }
     }
 
-    pub fn start_tests(&mut self, name: &str, mut tests: Vec<TestCondition>) {
-        for (id, test) in tests.iter_mut().enumerate() {
-            test.send_board(&mut self.ai);
-            println!("{}_test-{}: [{}]", name, id, {
-                match test.read_move(&mut self.ai) {
-                    TestResult::Success(location) => format!("success ({})", location.to_string()),
-                    TestResult::Fail(location, board) => {
-                        board.display();
-                        format!("failed ({})", location.to_string())
+    pub fn start_tests(&mut self, name: &str, mut tests: Vec<TestCondition>) -> Vec<TestResult> {
+        let mut results = Vec::new();
+        for (index, test) in tests.iter_mut().enumerate() {
+            if test.id.is_empty() {
+                test.id = index.to_string();
+            }
+            let timeout = test.time_limit.map(|time_limit| time_limit + TIMEOUT_GRACE);
+            let (result, elapsed) = match self.prepare(test.board_size, timeout) {
+                Ok(()) => {
+                    test.send_board(&mut self.ai);
+                    test.read_move(&mut self.ai)
+                }
+                Err(err) => {
+                    eprintln!("Error whilst preparing the AI: [{}]", err);
+                    (TestResult::Error, Duration::ZERO)
+                }
+            };
+            self.report
+                .push(TestReport::new(name, test, &result, elapsed));
+            println!(
+                "{}_test-{}: [{}] {}ms",
+                name,
+                test.id,
+                {
+                    match &result {
+                        TestResult::Success(location) => format!(
+                            "success ({})",
+                            location.to_notation(self.notation, test.board_size)
+                        ),
+                        TestResult::Fail(location, board) => {
+                            board.display_with(self.notation);
+                            format!(
+                                "failed ({})",
+                                location.to_notation(self.notation, board.size())
+                            )
+                        }
+                        TestResult::Timeout(_) => "failed (timeout)".to_string(),
+                        TestResult::Error => "failed".to_string(),
                     }
-                    TestResult::Error => "failed".to_string(),
+                },
+                elapsed.as_millis()
+            );
+            let failed = !matches!(result, TestResult::Success(_));
+            self.desynchronized = matches!(result, TestResult::Timeout(None));
+            results.push(result);
+            if self.fail_fast && failed {
+                break;
+            }
+        }
+        results
+    }
+
+    /// Outcome of every test played so far
+    pub fn report(&self) -> &Report {
+        &self.report
+    }
+
+    pub fn into_report(self) -> Report {
+        self.report
+    }
+
+    /// Run every suite, then print the score of each of them
+    pub fn run(&mut self, suites: Vec<Suite>, options: &TestOptions) {
+        self.isolation = options.isolation;
+        self.fail_fast = options.fail_fast;
+        self.notation = options.notation;
+        let mut scores: Vec<SuiteScore> = Vec::new();
+        let mut orientations: Vec<(Symmetry, usize, usize)> = Symmetry::ALL
+            .iter()
+            .map(|&symmetry| (symmetry, 0, 0))
+            .collect();
+        for suite in select(suites, options) {
+            let tests = suite.tests.clone();
+            let results = self.start_tests(&suite.name, suite.tests);
+            let mut score = SuiteScore {
+                name: suite.name,
+                ..Default::default()
+            };
+            for (test, result) in tests.iter().zip(&results) {
+                let success = matches!(result, TestResult::Success(_));
+                if let Some(orientation) = orientations
+                    .iter_mut()
+                    .find(|(symmetry, _, _)| *symmetry == test.orientation)
+                {
+                    orientation.1 += success as usize;
+                    orientation.2 += 1;
                 }
-            });
-        }
-    }
-
-    fn row_tests(&mut self, cell: Cell) {
-        let tests = vec![
-            TestCondition::new(
-                vec![
-                    ((2, 3).into(), cell),
-                    ((3, 3).into(), cell),
-                    ((4, 3).into(), cell),
-                    ((6, 3).into(), cell),
-                ],
-                vec![(5, 3).into()],
-            ),
-            TestCondition::new(
-                vec![
-                    ((2, 3).into(), cell),
-                    ((3, 3).into(), cell),
-                    ((5, 3).into(), cell),
-                    ((6, 3).into(), cell),
-                ],
-                vec![(4, 3).into()],
-            ),
-            TestCondition::new(
-                vec![
-                    ((2, 3).into(), cell),
-                    ((4, 3).into(), cell),
-                    ((5, 3).into(), cell),
-                    ((6, 3).into(), cell),
-                ],
-                vec![(3, 3).into()],
-            ),
-        ];
-
-        self.start_tests("col", tests);
-    }
-
-    fn diag1_tests(&mut self, cell: Cell) {
-        let tests = vec![
-            TestCondition::new(
-                vec![
-                    ((2, 3).into(), cell),
-                    ((3, 4).into(), cell),
-                    ((4, 5).into(), cell),
-                    ((6, 7).into(), cell),
-                ],
-                vec![(5, 6).into()],
-            ),
-            TestCondition::new(
-                vec![
-                    ((2, 3).into(), cell),
-                    ((4, 5).into(), cell),
-                    ((5, 6).into(), cell),
-                    ((6, 7).into(), cell),
-                ],
-                vec![(3, 4).into()],
-            ),
-            TestCondition::new(
-                vec![
-                    ((2, 3).into(), cell),
-                    ((3, 4).into(), cell),
-                    ((5, 6).into(), cell),
-                    ((6, 7).into(), cell),
-                ],
-                vec![(4, 5).into()],
-            ),
-        ];
-
-        self.start_tests("diag1", tests);
-    }
-
-    fn diag2_tests(&mut self, cell: Cell) {
-        let tests = vec![
-            TestCondition::new(
-                vec![
-                    ((2, 7).into(), cell),
-                    ((3, 6).into(), cell),
-                    ((4, 5).into(), cell),
-                    ((6, 3).into(), cell),
-                ],
-                vec![(5, 4).into()],
-            ),
-            TestCondition::new(
-                vec![
-                    ((2, 7).into(), cell),
-                    ((4, 5).into(), cell),
-                    ((5, 4).into(), cell),
-                    ((6, 3).into(), cell),
-                ],
-                vec![(3, 6).into()],
-            ),
-            TestCondition::new(
-                vec![
-                    ((2, 7).into(), cell),
-                    ((3, 6).into(), cell),
-                    ((5, 4).into(), cell),
-                    ((6, 3).into(), cell),
-                ],
-                vec![(4, 5).into()],
-            ),
-        ];
-
-        self.start_tests("diag2", tests);
-    }
-
-    fn col_tests(&mut self, cell: Cell) {
-        let tests = vec![
-            TestCondition::new(
-                vec![
-                    ((3, 2).into(), cell),
-                    ((3, 3).into(), cell),
-                    ((3, 4).into(), cell),
-                    ((3, 6).into(), cell),
-                ],
-                vec![(3, 5).into()],
-            ),
-            TestCondition::new(
-                vec![
-                    ((3, 2).into(), cell),
-                    ((3, 3).into(), cell),
-                    ((3, 5).into(), cell),
-                    ((3, 6).into(), cell),
-                ],
-                vec![(3, 4).into()],
-            ),
-            TestCondition::new(
-                vec![
-                    ((3, 2).into(), cell),
-                    ((3, 4).into(), cell),
-                    ((3, 5).into(), cell),
-                    ((3, 6).into(), cell),
-                ],
-                vec![(3, 3).into()],
-            ),
-        ];
-
-        self.start_tests("row", tests);
-    }
-
-    /// Tests will be ran on `ai1`
-    pub fn run(&mut self) {
-        let cells = vec![Cell::Ai1, Cell::Ai2];
-        for cell in cells {
-            match cell {
-                Cell::Ai1 => println!("ATTACKING TEST MODE!"),
-                _ => println!("DEFENDING TEST MODE!"),
+                score.add(success, test.score(result), test.max_score());
+            }
+            let failed = score.passed < score.total;
+            scores.push(score);
+            if self.fail_fast && failed {
+                println!("Stopping at the first failure");
+                break;
             }
-            self.col_tests(cell);
-            self.row_tests(cell);
-            self.diag1_tests(cell);
-            self.diag2_tests(cell);
         }
 
         // After every tests we can stop the Ai
         self.ai.stop();
+
+        println!("SCORES:");
+        let mut total = SuiteScore {
+            name: "total".to_string(),
+            ..Default::default()
+        };
+        for score in &scores {
+            total.passed += score.passed;
+            total.total += score.total;
+            total.points += score.points;
+            total.max_points += score.max_points;
+        }
+        let width = scores
+            .iter()
+            .map(|score| score.name.len())
+            .max()
+            .unwrap_or(0);
+        for score in scores.iter().chain([&total]) {
+            println!(
+                "{:width$}\t{}/{} passed\t{}/{} points",
+                score.name,
+                score.passed,
+                score.total,
+                score.points,
+                score.max_points,
+                width = width
+            );
+        }
+
+        if options.symmetries {
+            println!("ORIENTATIONS:");
+            for (symmetry, passed, total) in &orientations {
+                println!("{:14}\t{}/{} passed", symmetry.name(), passed, total);
+            }
+        }
+
+        if let Some(path) = &options.json {
+            if let Err(err) = self.report.write_json(path) {
+                eprintln!("Error whilst writing JSON report: [{}]", err);
+            }
+        }
+        if let Some(path) = &options.junit {
+            if let Err(err) = self.report.write_junit(path) {
+                eprintln!("Error whilst writing JUnit report: [{}]", err);
+            }
+        }
     }
 }
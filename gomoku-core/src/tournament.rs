@@ -0,0 +1,324 @@
+use std::{
+    collections::VecDeque,
+    path::Path,
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use crate::{
+    ai::Ai,
+    board::Cell,
+    game::{Game, GameEvent, GameResult, GameSettings, Reason},
+    report::suffixed,
+};
+
+/// Largest Elo difference an estimate can show, for brains winning or losing every game
+const MAX_ELO: f64 = 800.0;
+
+/// Round robin between brains
+#[derive(Clone, Debug)]
+pub struct TournamentSettings {
+    /// Specs of the brains
+    pub brains: Vec<String>,
+    /// Games of each pair of brains, the starting brain alternating
+    pub rounds: usize,
+    /// Games played at the same time
+    pub concurrency: usize,
+    /// Settings of every game, who starts being set by the tournament
+    pub game: GameSettings,
+}
+
+/// Game of a tournament, brains being indexes in `TournamentSettings::brains`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Pairing {
+    /// Index of the game in the tournament
+    pub game: usize,
+    /// Brain playing first
+    pub first: usize,
+    pub second: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchResult {
+    pub pairing: Pairing,
+    /// Result of the game, `Cell::Ai1` being the first brain
+    pub result: GameResult,
+}
+
+impl MatchResult {
+    /// Points of `brain` in this game: 1 for a win, 0.5 for a draw
+    pub fn points(&self, brain: usize) -> f64 {
+        match self.result.winner {
+            // Neither brain could start, both lose
+            None if self.result.reason == Reason::Crash => 0.0,
+            None => 0.5,
+            Some(Cell::Ai1) if brain == self.pairing.first => 1.0,
+            Some(Cell::Ai2) if brain == self.pairing.second => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Whether `brain` lost the game by `reason`
+    fn lost_by(&self, brain: usize, reason: &Reason) -> bool {
+        if self.result.reason != *reason {
+            return false;
+        }
+        match self.result.winner {
+            Some(Cell::Ai1) => brain == self.pairing.second,
+            Some(Cell::Ai2) => brain == self.pairing.first,
+            None => *reason == Reason::Crash,
+            _ => false,
+        }
+    }
+}
+
+/// What happens during a tournament, for whoever watches it
+#[derive(Clone, Debug)]
+pub enum TournamentEvent {
+    GameStarted(Pairing),
+    /// Event of the game `game`
+    Game(usize, GameEvent),
+    GameEnded(MatchResult),
+    Finished,
+}
+
+/// Results of a brain over the tournament
+#[derive(Clone, PartialEq, Debug)]
+pub struct Standing {
+    pub brain: usize,
+    pub games: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub score: f64,
+    /// Performance against the average opponent, 0 for an even score
+    pub elo: f64,
+    pub crashes: usize,
+    pub timeouts: usize,
+}
+
+/// Results of the games played so far
+#[derive(Clone, Default, Debug)]
+pub struct Tournament {
+    pub brains: Vec<String>,
+    pub results: Vec<MatchResult>,
+}
+
+impl Tournament {
+    pub fn new(brains: Vec<String>) -> Self {
+        Self {
+            brains,
+            results: Vec::new(),
+        }
+    }
+
+    /// Every game of `rounds` round robins, the starting brain alternating
+    pub fn pairings(brains: usize, rounds: usize) -> Vec<Pairing> {
+        let mut pairings = Vec::new();
+        for round in 0..rounds {
+            for first in 0..brains {
+                for second in first + 1..brains {
+                    let (first, second) = if round % 2 == 0 {
+                        (first, second)
+                    } else {
+                        (second, first)
+                    };
+                    pairings.push(Pairing {
+                        game: pairings.len(),
+                        first,
+                        second,
+                    });
+                }
+            }
+        }
+        pairings
+    }
+
+    pub fn push(&mut self, result: MatchResult) {
+        self.results.push(result);
+    }
+
+    /// Points of `brain` against `opponent`, none if they haven't played each other
+    pub fn crosstable(&self, brain: usize, opponent: usize) -> Option<f64> {
+        self.results
+            .iter()
+            .filter(|result| {
+                let pair = [result.pairing.first, result.pairing.second];
+                pair.contains(&brain) && pair.contains(&opponent) && brain != opponent
+            })
+            .map(|result| result.points(brain))
+            .reduce(|total, points| total + points)
+    }
+
+    /// Brains from the best score down
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = (0..self.brains.len())
+            .map(|brain| self.standing(brain))
+            .collect();
+        standings.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.brain.cmp(&b.brain)));
+        standings
+    }
+
+    fn standing(&self, brain: usize) -> Standing {
+        let played: Vec<&MatchResult> = self
+            .results
+            .iter()
+            .filter(|result| result.pairing.first == brain || result.pairing.second == brain)
+            .collect();
+        let count = |points: f64| {
+            played
+                .iter()
+                .filter(|result| result.points(brain) == points)
+                .count()
+        };
+        let faults = |reason: Reason| {
+            played
+                .iter()
+                .filter(|result| result.lost_by(brain, &reason))
+                .count()
+        };
+        let score = played.iter().map(|result| result.points(brain)).sum();
+
+        Standing {
+            brain,
+            games: played.len(),
+            wins: count(1.0),
+            draws: count(0.5),
+            losses: count(0.0),
+            score,
+            elo: elo(score, played.len()),
+            crashes: faults(Reason::Crash),
+            timeouts: faults(Reason::Timeout),
+        }
+    }
+
+    /// Play every game of `settings`, sending what happens to `events`
+    pub fn run(settings: &TournamentSettings, events: Sender<TournamentEvent>) -> Tournament {
+        Tournament::run_with(settings, events, Ai::from_spec)
+    }
+
+    /// Play every game of `settings`, the brains being started from their spec by `start`
+    pub fn run_with<F>(
+        settings: &TournamentSettings,
+        events: Sender<TournamentEvent>,
+        start: F,
+    ) -> Tournament
+    where
+        F: Fn(&str) -> Result<Ai, String> + Send + Sync + 'static,
+    {
+        let start = Arc::new(start);
+        let pairings = Tournament::pairings(settings.brains.len(), settings.rounds);
+        let queue = Arc::new(Mutex::new(VecDeque::from(pairings)));
+        let (results, received) = mpsc::channel();
+
+        let workers: Vec<_> = (0..settings.concurrency.max(1))
+            .map(|_| {
+                let queue = queue.clone();
+                let results = results.clone();
+                let events = events.clone();
+                let settings = settings.clone();
+                let start = start.clone();
+                thread::spawn(move || loop {
+                    let Some(pairing) = queue.lock().unwrap().pop_front() else {
+                        break;
+                    };
+                    let result = play(&settings, pairing, &events, start.as_ref());
+                    let _ = events.send(TournamentEvent::GameEnded(result.clone()));
+                    let _ = results.send(result);
+                })
+            })
+            .collect();
+        drop(results);
+
+        let mut tournament = Tournament::new(settings.brains.clone());
+        for result in received {
+            tournament.push(result);
+        }
+        for worker in workers {
+            let _ = worker.join();
+        }
+        let _ = events.send(TournamentEvent::Finished);
+        tournament
+    }
+}
+
+/// Play the game of `pairing` between the brains started by `start`, forwarding its events
+fn play(
+    settings: &TournamentSettings,
+    pairing: Pairing,
+    events: &Sender<TournamentEvent>,
+    start: &dyn Fn(&str) -> Result<Ai, String>,
+) -> MatchResult {
+    let _ = events.send(TournamentEvent::GameStarted(pairing));
+    let first = start(&settings.brains[pairing.first]);
+    let second = start(&settings.brains[pairing.second]);
+    let result = match (first, second) {
+        (Ok(first), Ok(second)) => {
+            let mut game = Game::new(first, second);
+            let (game_events, received) = mpsc::channel();
+            game.set_events(Some(game_events));
+            let forward = {
+                let events = events.clone();
+                thread::spawn(move || {
+                    for event in received {
+                        let _ = events.send(TournamentEvent::Game(pairing.game, event));
+                    }
+                })
+            };
+            let result = game.run(&GameSettings {
+                ai1_starting: true,
+                trace: numbered(&settings.game.trace, pairing.game),
+                record: numbered(&settings.game.record, pairing.game),
+                ..settings.game.clone()
+            });
+            drop(game);
+            let _ = forward.join();
+            result
+        }
+        (first, second) => {
+            // A brain which can't start loses, the other one being stopped
+            let failed = [first, second].map(|ai| match ai {
+                Ok(mut ai) => {
+                    ai.stop();
+                    false
+                }
+                Err(err) => {
+                    eprintln!("Error whilst starting the AI: [{}]", err);
+                    true
+                }
+            });
+            let winner = match failed {
+                [true, false] => Some(Cell::Ai2),
+                [false, true] => Some(Cell::Ai1),
+                // Neither started, which counts as a loss for both
+                _ => None,
+            };
+            GameResult::new(winner, Reason::Crash)
+        }
+    };
+    MatchResult { pairing, result }
+}
+
+/// `path` with the number of the game before its extension, as `games-3.psq`
+fn numbered(path: &Option<String>, game: usize) -> Option<String> {
+    let path = suffixed(Path::new(path.as_ref()?), &game.to_string());
+    Some(path.to_string_lossy().to_string())
+}
+
+/// Elo difference with the average opponent giving `score` out of `games`
+pub fn elo(score: f64, games: usize) -> f64 {
+    if games == 0 {
+        return 0.0;
+    }
+    let ratio = score / games as f64;
+    if ratio <= 0.0 {
+        return -MAX_ELO;
+    }
+    if ratio >= 1.0 {
+        return MAX_ELO;
+    }
+    (-400.0 * (1.0 / ratio - 1.0).log10()).clamp(-MAX_ELO, MAX_ELO)
+}
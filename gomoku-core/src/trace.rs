@@ -0,0 +1,351 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ai::Ai,
+    game::{Game, GameSettings},
+    location::Location,
+    transport::Transport,
+};
+
+/// Version of the trace format, given by the `TRACE` line heading the traces
+///
+/// Traces without it are version 1, whose brain names are not escaped.
+pub const TRACE_VERSION: u32 = 2;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Direction {
+    /// Line sent by the referee to a brain
+    Send,
+    /// Line received by the referee from a brain
+    Recv,
+    /// Information about the session itself (settings, players)
+    Meta,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let symbol = match self {
+            Direction::Send => "->",
+            Direction::Recv => "<-",
+            Direction::Meta => "##",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+impl Direction {
+    pub fn from_string(target: &str) -> Result<Direction, String> {
+        match target {
+            "->" => Ok(Direction::Send),
+            "<-" => Ok(Direction::Recv),
+            "##" => Ok(Direction::Meta),
+            _ => Err(format!("unknown direction [{}]", target)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TraceEntry {
+    pub elapsed: Duration,
+    pub direction: Direction,
+    pub brain: String,
+    pub line: String,
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}",
+            self.elapsed.as_micros(),
+            self.direction,
+            escape(&self.brain),
+            self.line
+        )
+    }
+}
+
+impl TraceEntry {
+    pub fn from_string(target: &str) -> Result<TraceEntry, String> {
+        Self::parse(target, TRACE_VERSION)
+    }
+
+    /// Parse a line of a trace written in the format `version`
+    pub fn parse(target: &str, version: u32) -> Result<TraceEntry, String> {
+        let fields: Vec<&str> = target.splitn(4, '\t').collect();
+
+        if fields.len() != 4 {
+            return Err(format!("malformed trace line [{}]", target));
+        }
+
+        let micros: u64 = match fields[0].parse() {
+            Ok(micros) => micros,
+            Err(err) => return Err(err.to_string()),
+        };
+
+        Ok(TraceEntry {
+            elapsed: Duration::from_micros(micros),
+            direction: Direction::from_string(fields[1])?,
+            brain: if version < 2 {
+                fields[2].to_string()
+            } else {
+                unescape(fields[2])
+            },
+            line: fields[3].to_string(),
+        })
+    }
+}
+
+/// Brain name with its backslashes, tabs and line breaks escaped
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(name: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Protocol trace of every line exchanged between the referee and the brains
+///
+/// Each line holds the time elapsed since the trace was created (in µs),
+/// the direction, the brain name (escaped) and the raw protocol line, tab separated.
+/// The first line gives the version of the format.
+pub struct Trace {
+    file: BufWriter<File>,
+    start: Instant,
+}
+
+pub type SharedTrace = Arc<Mutex<Trace>>;
+
+impl Trace {
+    pub fn create(path: &str) -> Result<Self, String> {
+        match File::create(path) {
+            Ok(file) => {
+                let mut trace = Self {
+                    file: BufWriter::new(file),
+                    start: Instant::now(),
+                };
+                trace.record(
+                    Direction::Meta,
+                    "referee",
+                    &format!("TRACE {}", TRACE_VERSION),
+                );
+                Ok(trace)
+            }
+            Err(err) => Err(format!("failed to create trace [{}]: {}", path, err)),
+        }
+    }
+
+    pub fn shared(self) -> SharedTrace {
+        Arc::new(Mutex::new(self))
+    }
+
+    pub fn record(&mut self, direction: Direction, brain: &str, line: &str) {
+        let entry = TraceEntry {
+            elapsed: self.start.elapsed(),
+            direction,
+            brain: brain.to_string(),
+            line: line.trim_end_matches(['\r', '\n']).to_string(),
+        };
+
+        if let Err(err) = writeln!(self.file, "{}", entry).and_then(|_| self.file.flush()) {
+            eprintln!("Error whilst writing the trace: [{}]", err);
+        }
+    }
+
+    /// Version of the format of a trace starting with `first_line`
+    fn version(first_line: &str) -> u32 {
+        let fields: Vec<&str> = first_line.splitn(4, '\t').collect();
+        match fields.as_slice() {
+            [_, "##", _, header] => header
+                .strip_prefix("TRACE ")
+                .and_then(|version| version.trim().parse().ok())
+                .unwrap_or(1),
+            _ => 1,
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Vec<TraceEntry>, String> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) => return Err(format!("failed to open trace [{}]: {}", path, err)),
+        };
+
+        let mut entries = Vec::new();
+        let mut version = None;
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|err| err.to_string())?;
+            if line.is_empty() {
+                continue;
+            }
+            let version = *version.get_or_insert_with(|| Self::version(&line));
+            if version > TRACE_VERSION {
+                return Err(format!("unsupported trace version [{}]", version));
+            }
+            entries.push(TraceEntry::parse(&line, version)?);
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Fake brain answering with what a brain answered in a trace,
+/// and checking that the referee sends exactly what it sent back then
+pub struct ReplayTransport {
+    brain: String,
+    expected: VecDeque<TraceEntry>,
+    divergences: Arc<Mutex<Vec<String>>>,
+}
+
+impl ReplayTransport {
+    pub fn new(brain: &str, entries: &[TraceEntry], divergences: Arc<Mutex<Vec<String>>>) -> Self {
+        let expected = entries
+            .iter()
+            .filter(|entry| entry.brain == brain && entry.direction != Direction::Meta)
+            .cloned()
+            .collect();
+
+        Self {
+            brain: brain.to_string(),
+            expected,
+            divergences,
+        }
+    }
+
+    fn diverge(&self, message: String) {
+        self.divergences
+            .lock()
+            .unwrap()
+            .push(format!("[{}] {}", self.brain, message));
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn read(&mut self) -> Result<String, Error> {
+        match self.expected.front() {
+            Some(entry) if entry.direction == Direction::Recv => {
+                let line = format!("{}\n", entry.line);
+                self.expected.pop_front();
+                Ok(line)
+            }
+            Some(entry) => {
+                let message = format!("read a line whilst [{}] was sent", entry.line);
+                self.diverge(message);
+                Err(Error::new(ErrorKind::InvalidData, "trace diverged"))
+            }
+            None => Err(Error::new(ErrorKind::UnexpectedEof, "end of trace")),
+        }
+    }
+
+    fn write(&mut self, target: String) -> Result<(), Error> {
+        match self.expected.pop_front() {
+            Some(entry) if entry.direction == Direction::Send && entry.line == target => {}
+            Some(entry) => {
+                self.diverge(format!("sent [{}] instead of [{}]", target, entry.line));
+                self.expected.push_front(entry);
+            }
+            None => self.diverge(format!("sent [{}] after the end of the trace", target)),
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, _timeout: Option<Duration>) {}
+
+    fn close(&mut self) {
+        if let Some(entry) = self.expected.front() {
+            let message = format!("stopped before [{}] {}", entry.direction, entry.line);
+            self.diverge(message);
+        }
+    }
+}
+
+/// Play a traced game again against its own recording
+///
+/// Returns every point where the referee did not behave like in the trace.
+pub fn replay(path: &str) -> Result<Vec<String>, String> {
+    let entries = Trace::load(path)?;
+
+    let mut settings: Option<GameSettings> = None;
+    let mut players: Vec<String> = Vec::new();
+    let mut opening = Vec::new();
+    for entry in entries.iter().filter(|e| e.direction == Direction::Meta) {
+        let fields: Vec<&str> = entry.line.split(' ').collect();
+        match fields.as_slice() {
+            // Version 1 traces have no turn timeout
+            ["SETTINGS", size, starting, timeout @ ..] if timeout.len() <= 1 => {
+                let timeout: u64 = match timeout.first() {
+                    Some(timeout) => timeout.parse().map_err(|_| "invalid turn timeout")?,
+                    None => 0,
+                };
+                settings = Some(GameSettings {
+                    board_size: size.parse().map_err(|_| "invalid board size")?,
+                    ai1_starting: *starting == "1",
+                    timeout_turn: (timeout > 0).then(|| Duration::from_millis(timeout)),
+                    trace: None,
+                    record: None,
+                    opening: Vec::new(),
+                })
+            }
+            ["OPENING", moves @ ..] => {
+                opening = moves
+                    .iter()
+                    .map(|coords| Location::from_string(coords.to_string()))
+                    .collect::<Result<_, _>>()?
+            }
+            ["PLAYER", _] => players.push(entry.brain.clone()),
+            _ => {}
+        }
+    }
+
+    let mut settings = settings.ok_or("no game settings in the trace")?;
+    settings.opening = opening;
+    if players.len() != 2 {
+        return Err("expected two players in the trace".to_string());
+    }
+
+    let divergences = Arc::new(Mutex::new(Vec::new()));
+    let mut ais = players.iter().map(|brain| {
+        let mut ai = Ai::new(Box::new(ReplayTransport::new(
+            brain,
+            &entries,
+            divergences.clone(),
+        )));
+        ai.name = brain.clone();
+        ai
+    });
+    let (ai1, ai2) = (ais.next().unwrap(), ais.next().unwrap());
+
+    Game::new(ai1, ai2).run(&settings);
+
+    let divergences = divergences.lock().unwrap().clone();
+    Ok(divergences)
+}
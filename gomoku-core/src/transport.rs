@@ -0,0 +1,16 @@
+use std::{io::Error, time::Duration};
+
+/// Line based channel used to talk to a brain, whatever it runs on.
+pub trait Transport: Send {
+    fn read(&mut self) -> Result<String, Error>;
+
+    fn write(&mut self, target: String) -> Result<(), Error>;
+
+    fn flush(&mut self) -> Result<(), Error>;
+
+    /// Make `read` fail with `ErrorKind::TimedOut` when nothing comes in time
+    fn set_timeout(&mut self, timeout: Option<Duration>);
+
+    /// Release the underlying resources once `END` has been sent
+    fn close(&mut self);
+}
@@ -0,0 +1,34 @@
+use gomoku_core::location::{column_name, Location, Notation};
+
+#[test]
+fn algebraic_notation_counts_rows_from_the_bottom() {
+    assert_eq!(Location::from_algebraic("h8", 15), Ok((7, 7).into()));
+    assert_eq!(Location::from_algebraic("a1", 20), Ok((0, 19).into()));
+    assert_eq!(Location::from((19, 0)).to_algebraic(20), "t20");
+    assert!(Location::from_algebraic("p1", 15).is_err());
+    assert!(Location::from_algebraic("a16", 15).is_err());
+    assert_eq!(Location::parse("3,4", 15), Ok((3, 4).into()));
+}
+
+#[test]
+fn columns_past_z_take_two_letters() {
+    assert_eq!(column_name(25), "z");
+    assert_eq!(column_name(26), "aa");
+    assert_eq!(column_name(29), "ad");
+    assert_eq!(Location::from((29, 0)).to_algebraic(30), "ad30");
+    assert_eq!(Location::from_algebraic("ad30", 30), Ok((29, 0).into()));
+    assert_eq!(Location::from_algebraic("AA1", 30), Ok((26, 29).into()));
+    assert!(Location::from_algebraic("ae1", 30).is_err());
+    assert!(Location::from_algebraic("zzzzzzzzzzzzzzzzzzzz1", 30).is_err());
+    assert!(Location::from_algebraic("8", 30).is_err());
+}
+
+#[test]
+fn notations_are_named() {
+    assert_eq!(Notation::from_string("algebraic"), Ok(Notation::Algebraic));
+    assert_eq!(
+        Location::from((7, 7)).to_notation(Notation::Protocol, 15),
+        "7,7"
+    );
+    assert!(Notation::from_string("h8").is_err());
+}
@@ -0,0 +1,342 @@
+use std::time::Duration;
+
+use gomoku_core::{
+    ai::Ai,
+    board::Cell,
+    game::{Game, GameSettings, Reason},
+    location::Location,
+    mock::{Misbehaviour, MockBrain},
+    record::GameRecord,
+    rif::RifDatabase,
+};
+
+fn row(y: usize, count: usize) -> Vec<Location> {
+    (0..count).map(|x| (x, y).into()).collect()
+}
+
+#[test]
+fn game_moves_are_recorded_in_play_order() {
+    let mut game = Game::new(
+        Ai::new(Box::new(MockBrain::new(row(0, 5)))).named("first"),
+        Ai::new(Box::new(MockBrain::new(row(1, 5)))).named("second"),
+    );
+    game.run(&GameSettings {
+        board_size: 20,
+        ai1_starting: true,
+        timeout_turn: None,
+        trace: None,
+        record: None,
+        opening: Vec::new(),
+    });
+
+    let record = game.record();
+    assert_eq!(record.players, ["first".to_string(), "second".to_string()]);
+    assert_eq!(record.moves.len(), 9);
+    assert_eq!(record.moves[1].location, (0, 1).into());
+    assert_eq!(record.board(9).get(4, 0), Cell::Ai1);
+}
+
+#[test]
+fn psq_uses_one_based_coordinates() {
+    let mut record = GameRecord::new(15, ["black".to_string(), "white".to_string()]);
+    record.push((7, 7).into(), Duration::from_millis(12));
+    record.push((0, 14).into(), Duration::from_millis(340));
+
+    assert_eq!(
+        record.to_psq(),
+        "Piskvorky 15x15, 11:11, 0\n8,8,12\n1,15,340\nblack\nwhite\n-1\n"
+    );
+    assert_eq!(GameRecord::from_psq(&record.to_psq()), Ok(record));
+}
+
+#[test]
+fn psq_from_piskvork_is_loaded() {
+    let psq = "Piskvorky 20x20, 11:11, 0\r\n10,10,0\r\n11,11,1042\r\n10,11,873\r\n\
+               pbrain-embryo.exe\r\npbrain-yixin.exe\r\n-1\r\n";
+    let record = GameRecord::from_psq(psq).unwrap();
+
+    assert_eq!(record.board_size, 20);
+    assert_eq!(record.moves.len(), 3);
+    assert_eq!(record.moves[2].location, (9, 10).into());
+    assert_eq!(record.moves[1].time, Duration::from_millis(1042));
+    assert_eq!(record.players[1], "pbrain-yixin.exe");
+}
+
+const RIF: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<database>
+  <players>
+    <player id="1" name="Ana" surname="Lee" country="1"/>
+    <player id="2" name="Bo" surname="Kim" country="2"/>
+  </players>
+  <openings>
+    <opening id="3" name="Kagetsu">h8 h7 i7</opening>
+  </openings>
+  <games>
+    <game id="10" black="1" white="2" bresult="1" opening="3">
+      <move>h8 h7 i7 i8 j9</move>
+    </game>
+  </games>
+</database>"#;
+
+#[test]
+fn rif_games_and_openings_are_parsed() {
+    let database = RifDatabase::parse(RIF).unwrap();
+
+    let opening = database.opening("kagetsu").unwrap();
+    assert_eq!(
+        opening.moves,
+        vec![(7, 7).into(), (7, 8).into(), (8, 8).into()]
+    );
+
+    let game = &database.games[0];
+    assert_eq!(game.black, "Ana Lee");
+    assert_eq!(game.black_result, Some(1.0));
+    assert_eq!(game.moves[4], (9, 6).into());
+    assert_eq!(game.to_record().board_size, 15);
+}
+
+#[test]
+fn record_position_expects_the_move_played() {
+    let record = RifDatabase::parse(RIF).unwrap().games[0].to_record();
+    let test = record.position(3).unwrap();
+
+    assert_eq!(test.board().len(), 3);
+    assert_eq!(test.expected_moves(), [Location::from((8, 7))]);
+    assert_eq!(test.to_move, Cell::Ai2);
+    assert!(record.position(5).is_none());
+}
+
+#[test]
+fn game_starts_from_opening() {
+    let brain2 = MockBrain::new(vec![(1, 0).into(), (2, 0).into()]);
+    let received = brain2.received();
+    let mut game = Game::new(
+        Ai::new(Box::new(MockBrain::new(row(5, 5)))),
+        Ai::new(Box::new(brain2)),
+    );
+    game.run(&GameSettings {
+        board_size: 20,
+        ai1_starting: true,
+        timeout_turn: None,
+        trace: None,
+        record: None,
+        opening: vec![(9, 9).into(), (0, 0).into(), (9, 10).into()],
+    });
+
+    assert_eq!(
+        received.lock().unwrap()[2..7],
+        ["BOARD", "0,0,1", "9,9,2", "9,10,2", "DONE"]
+    );
+    assert_eq!(game.record().moves[3].location, (1, 0).into());
+}
+
+#[test]
+fn invalid_opening_ends_the_game() {
+    let brain1 = MockBrain::new(row(5, 5));
+    let received = brain1.received();
+    let mut game = Game::new(
+        Ai::new(Box::new(brain1)),
+        Ai::new(Box::new(MockBrain::new(row(6, 5)))),
+    );
+    let settings = GameSettings {
+        board_size: 20,
+        ai1_starting: true,
+        timeout_turn: None,
+        trace: None,
+        record: None,
+        opening: vec![(9, 9).into(), (9, 10).into(), (9, 9).into()],
+    };
+    let result = game.run(&settings);
+
+    assert_eq!(settings.check_opening().unwrap_err(), "9,9 is played twice");
+    assert_eq!(result.winner, None);
+    assert_eq!(
+        result.reason,
+        Reason::InvalidOpening("9,9 is played twice".to_string())
+    );
+    assert!(!received.lock().unwrap().iter().any(|line| line == "BEGIN"));
+    let outside = GameSettings {
+        opening: vec![(20, 0).into()],
+        ..settings
+    };
+    assert_eq!(
+        outside.check_opening().unwrap_err(),
+        "20,0 is outside of the board"
+    );
+}
+
+#[test]
+fn rif_moves_played_twice_are_rejected() {
+    let opening = RIF.replace("h8 h7 i7<", "h8 h7 h8<");
+    let game = RIF.replace("<move>h8 h7", "<move>h8 h8");
+
+    assert_eq!(
+        RifDatabase::parse(&opening).unwrap_err(),
+        "opening 3: h8 is played twice"
+    );
+    assert!(RifDatabase::parse(&game)
+        .unwrap_err()
+        .ends_with("h8 is played twice"));
+}
+
+#[test]
+fn brain_messages_are_kept_with_their_move() {
+    let mut game = Game::new(
+        Ai::new(Box::new(MockBrain::new(row(0, 5)).saying("eval 120"))),
+        Ai::new(Box::new(MockBrain::new(row(1, 5)))),
+    );
+    let result = game.run(&GameSettings {
+        board_size: 20,
+        ai1_starting: true,
+        timeout_turn: None,
+        trace: None,
+        record: None,
+        opening: Vec::new(),
+    });
+
+    assert_eq!(result.winner, Some(Cell::Ai1));
+    assert_eq!(game.record().moves[0].messages, ["MESSAGE eval 120"]);
+    assert!(game.record().moves[1].messages.is_empty());
+}
+
+#[test]
+fn sgf_round_trips_with_comments() {
+    let mut record = GameRecord::new(15, ["black".to_string(), "white [2]".to_string()]);
+    record.push((7, 7).into(), Duration::ZERO);
+    record.push((8, 6).into(), Duration::ZERO);
+    record.moves[1].messages = vec!["MESSAGE a]b".to_string(), "DEBUG c\\d".to_string()];
+
+    let sgf = record.to_sgf();
+    assert!(sgf.starts_with("(;GM[4]FF[4]"));
+    assert!(sgf.contains(";B[hh]\n;W[ig]C[MESSAGE a\\]b\nDEBUG c\\\\d]\n"));
+    assert_eq!(GameRecord::from_sgf(&sgf), Ok(record));
+}
+
+#[test]
+fn sgf_uses_capitals_past_the_alphabet() {
+    let mut record = GameRecord::new(30, ["Ana".to_string(), "Bo".to_string()]);
+    record.push((0, 25).into(), Duration::ZERO);
+    record.push((26, 29).into(), Duration::ZERO);
+
+    let sgf = record.to_sgf();
+    assert!(sgf.contains(";B[az]"), "{}", sgf);
+    assert!(sgf.contains(";W[AD]"), "{}", sgf);
+    assert_eq!(GameRecord::from_sgf(&sgf), Ok(record));
+}
+
+#[test]
+fn sgf_main_line_is_loaded() {
+    let sgf = "(;GM[4]SZ[15]PB[Ana]PW[Bo];B[hh]C[center];W[ii](;B[gg];W[jj])(;B[aa]))";
+    let record = GameRecord::from_sgf(sgf).unwrap();
+
+    assert_eq!(record.players, ["Ana".to_string(), "Bo".to_string()]);
+    assert_eq!(record.moves.len(), 4);
+    assert_eq!(record.moves[0].messages, ["center"]);
+    assert_eq!(record.moves[3].location, (9, 9).into());
+}
+
+#[test]
+fn json_record_keeps_brains_settings_and_result() {
+    let mut game = Game::new(
+        Ai::new(Box::new(MockBrain::new(row(0, 5)))).named("./first"),
+        Ai::new(Box::new(
+            MockBrain::new(row(1, 5)).saying("depth 4 eval -120"),
+        ))
+        .named("./second"),
+    );
+    game.run(&GameSettings {
+        board_size: 20,
+        ai1_starting: false,
+        timeout_turn: Some(Duration::from_millis(1000)),
+        trace: None,
+        record: None,
+        opening: Vec::new(),
+    });
+
+    let record = game.record();
+    assert_eq!(record.brains[0].spec, "./second");
+    assert_eq!(record.brains[0].version.as_deref(), Some("1.0"));
+    assert_eq!(
+        record.settings.timeout_turn,
+        Some(Duration::from_millis(1000))
+    );
+    assert!(!record.settings.ai1_starting);
+    assert_eq!(record.moves[0].evaluation, Some(-120));
+    assert_eq!(record.result.as_ref().unwrap().reason, Reason::Five);
+    // Ai 2 started and won, so the first player did
+    assert_eq!(record.result.as_ref().unwrap().winner, Some(Cell::Ai1));
+
+    let loaded = GameRecord::from_json(&record.to_json().unwrap()).unwrap();
+    assert_eq!(&loaded, record);
+    assert_eq!(loaded.board(9).get(4, 1), Cell::Ai1);
+}
+
+#[test]
+fn brains_introduce_themselves_before_the_game() {
+    let brain2 = MockBrain::misbehaving(Misbehaviour::Hang);
+    let received = brain2.received();
+    let mut game = Game::new(
+        Ai::new(Box::new(MockBrain::new(row(0, 5)))),
+        Ai::new(Box::new(brain2)),
+    );
+    let result = game.run(&GameSettings {
+        board_size: 20,
+        ai1_starting: true,
+        timeout_turn: Some(Duration::from_millis(50)),
+        trace: None,
+        record: None,
+        opening: Vec::new(),
+    });
+    let received = received.lock().unwrap();
+    let position = |command: &str| {
+        received
+            .iter()
+            .position(|line| line.starts_with(command))
+            .unwrap()
+    };
+
+    assert_eq!(result.reason, Reason::Timeout);
+    assert_eq!(game.record().brains[1].version.as_deref(), Some("1.0"));
+    // Not asked again once timed out, its late answer would be taken for the move
+    assert_eq!(received.iter().filter(|line| *line == "ABOUT").count(), 1);
+    assert!(position("ABOUT") < position("TURN"));
+}
+
+#[test]
+fn records_which_cannot_be_replayed_are_rejected() {
+    let json = |moves: &str| {
+        format!(
+            r#"{{"board_size": 15, "players": ["a", "b"], "moves": [{}]}}"#,
+            moves
+        )
+    };
+    let outside = json(r#"{"location": {"x": 15, "y": 7}, "time_ms": 1}"#);
+    let twice = json(
+        r#"{"location": {"x": 7, "y": 7}, "time_ms": 1},
+           {"location": {"x": 7, "y": 7}, "time_ms": 1}"#,
+    );
+
+    assert_eq!(
+        GameRecord::from_json(&outside).unwrap_err(),
+        "move outside of the board [15,7]"
+    );
+    assert_eq!(
+        GameRecord::from_json(&twice).unwrap_err(),
+        "move on a stone [7,7]"
+    );
+    assert_eq!(
+        GameRecord::from_psq("Piskvorky 15x15, 11:11, 0\n8,8,10\n8,8,10\n").unwrap_err(),
+        "move on a stone [7,7]"
+    );
+}
+
+#[test]
+fn json_record_without_metadata_is_loaded() {
+    let json = r#"{"board_size": 15, "players": ["a", "b"],
+                   "moves": [{"location": {"x": 7, "y": 7}, "time_ms": 12.5}]}"#;
+    let record = GameRecord::from_json(json).unwrap();
+
+    assert_eq!(record.moves[0].time, Duration::from_micros(12500));
+    assert_eq!(record.settings.rule, "freestyle");
+    assert!(record.result.is_none());
+}
@@ -0,0 +1,590 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use gomoku_core::{
+    ai::{Ai, Fault, TAKEBACK_TIMEOUT},
+    board::Cell,
+    game::{Game, GameEvent, GameResult, GameSettings, Reason},
+    location::Location,
+    mock::{Misbehaviour, MockBrain},
+    player::{Human, Player, PlayerMove},
+    test::{Isolation, Test, TestCondition, TestResult},
+    transport::Transport,
+};
+
+fn settings() -> GameSettings {
+    GameSettings {
+        board_size: 20,
+        ai1_starting: true,
+        timeout_turn: None,
+        trace: None,
+        record: None,
+        opening: Vec::new(),
+    }
+}
+
+fn row(y: usize, count: usize) -> Vec<Location> {
+    (0..count).map(|x| (x, y).into()).collect()
+}
+
+fn play(brain1: MockBrain, brain2: MockBrain) -> GameResult {
+    Game::new(Ai::new(Box::new(brain1)), Ai::new(Box::new(brain2))).run(&settings())
+}
+
+#[test]
+fn five_in_a_row_wins() {
+    let result = play(MockBrain::new(row(0, 5)), MockBrain::new(row(1, 5)));
+
+    assert_eq!(result.winner, Some(Cell::Ai1));
+    assert_eq!(result.reason, Reason::Five);
+    assert_eq!(result.moves, 9);
+}
+
+#[test]
+fn game_events_are_streamed() {
+    let (sender, receiver) = mpsc::channel();
+    let mut game = Game::new(
+        Ai::new(Box::new(MockBrain::new(row(0, 5)))),
+        Ai::new(Box::new(MockBrain::new(row(1, 5)))),
+    );
+    game.set_events(Some(sender));
+    let result = game.run(&settings());
+    let events: Vec<GameEvent> = receiver.try_iter().collect();
+
+    assert_eq!(events.len(), 11);
+    assert!(matches!(
+        events[0],
+        GameEvent::Started {
+            board_size: 20,
+            first: Cell::Ai1,
+            ..
+        }
+    ));
+    assert!(matches!(
+        &events[2],
+        GameEvent::Move { cell: Cell::Ai2, location, .. } if *location == (0, 1).into()
+    ));
+    assert!(matches!(&events[10], GameEvent::Ended(ended) if *ended == result));
+}
+
+#[test]
+fn human_takes_back_moves() {
+    let (human, moves) = Human::new("human");
+    let brain = MockBrain::new(row(1, 5));
+    let received = brain.received();
+    for input in [
+        PlayerMove::Play((0, 0).into()),
+        PlayerMove::Undo,
+        PlayerMove::Play((0, 0).into()),
+        // Misclick on a stone, played again
+        PlayerMove::Play((1, 1).into()),
+    ] {
+        moves.send(input).unwrap();
+    }
+    for location in row(0, 5).into_iter().skip(1) {
+        moves.send(PlayerMove::Play(location)).unwrap();
+    }
+
+    let mut game = Game::with_players(Player::Human(human), Player::Ai(Ai::new(Box::new(brain))));
+    let result = game.run(&settings());
+
+    assert_eq!(result.winner, Some(Cell::Ai1));
+    assert_eq!(result.moves, 9);
+    assert_eq!(game.record().moves[1].location, (1, 1).into());
+    let received = received.lock().unwrap();
+    assert!(received.contains(&"TAKEBACK 0,1".to_string()));
+    assert!(received.contains(&"TAKEBACK 0,0".to_string()));
+}
+
+#[test]
+fn humans_take_back_the_move_just_played() {
+    let (human1, moves1) = Human::new("black");
+    let (human2, moves2) = Human::new("white");
+    for location in [(0, 0), (5, 5), (1, 0), (2, 0), (3, 0), (4, 0)] {
+        moves1.send(PlayerMove::Play(location.into())).unwrap();
+    }
+    moves2.send(PlayerMove::Play((0, 1).into())).unwrap();
+    // Takes back black's 5,5, black playing again
+    moves2.send(PlayerMove::Undo).unwrap();
+    for location in [(1, 1), (2, 1), (3, 1)] {
+        moves2.send(PlayerMove::Play(location.into())).unwrap();
+    }
+
+    let mut game = Game::with_players(Player::Human(human1), Player::Human(human2));
+    let result = game.run(&settings());
+    let moves: Vec<Location> = game
+        .record()
+        .moves
+        .iter()
+        .map(|played| played.location)
+        .collect();
+
+    assert_eq!(result.winner, Some(Cell::Ai1));
+    assert_eq!(result.moves, 9);
+    assert_eq!(moves[..3], [(0, 0).into(), (0, 1).into(), (1, 0).into()]);
+    assert!(!moves.contains(&(5, 5).into()));
+}
+
+/// Transport answering `answers` in order, line endings included, then timing out, keeping the read timeouts it was given
+struct Scripted {
+    answers: Vec<&'static str>,
+    timeouts: Arc<Mutex<Vec<Option<Duration>>>>,
+}
+
+impl Transport for Scripted {
+    fn read(&mut self) -> io::Result<String> {
+        if self.answers.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "no answer"));
+        }
+        Ok(self.answers.remove(0).to_string())
+    }
+
+    fn write(&mut self, _target: String) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeouts.lock().unwrap().push(timeout);
+    }
+
+    fn close(&mut self) {}
+}
+
+fn scripted(answers: Vec<&'static str>) -> (Ai, Arc<Mutex<Vec<Option<Duration>>>>) {
+    let timeouts = Arc::new(Mutex::new(Vec::new()));
+    let transport = Scripted {
+        answers,
+        timeouts: timeouts.clone(),
+    };
+    (Ai::new(Box::new(transport)), timeouts)
+}
+
+#[test]
+fn take_back_skips_messages_before_its_answer() {
+    let (mut ai, _) = scripted(vec![
+        "MESSAGE undoing\n",
+        "DEBUG depth 3\n",
+        "OK\n",
+        "4,5\n",
+    ]);
+
+    ai.take_back((4, 4).into());
+
+    assert_eq!(ai.read_move(), Ok((4, 5).into()));
+    assert_eq!(ai.take_messages(), ["MESSAGE undoing", "DEBUG depth 3"]);
+}
+
+#[test]
+fn moves_ending_with_crlf_are_read() {
+    let (mut ai, _) = scripted(vec!["MESSAGE windows\r\n", "7,7\r\n", "8,8\r\n"]);
+
+    assert_eq!(ai.read_move(), Ok((7, 7).into()));
+    assert_eq!(ai.take_messages(), ["MESSAGE windows"]);
+    assert_eq!(ai.read_move(), Ok((8, 8).into()));
+}
+
+#[test]
+fn take_back_waits_for_its_answer_a_bounded_time() {
+    let (mut ai, timeouts) = scripted(Vec::new());
+    ai.set_timeout(Some(Duration::from_millis(200)));
+    timeouts.lock().unwrap().clear();
+
+    ai.take_back((4, 4).into());
+
+    let timeouts = timeouts.lock().unwrap();
+    assert!(timeouts[0].is_some_and(|timeout| timeout <= TAKEBACK_TIMEOUT));
+    // The turn timeout is back for the next move
+    assert_eq!(timeouts.last(), Some(&Some(Duration::from_millis(200))));
+}
+
+#[test]
+fn garbage_output_loses() {
+    let ai1_moves = vec![(0, 0).into(), (5, 5).into(), (9, 0).into(), (7, 7).into()];
+    let result = play(
+        MockBrain::new(ai1_moves).then(Misbehaviour::Garbage),
+        MockBrain::new(row(10, 5)),
+    );
+
+    assert_eq!(result.winner, Some(Cell::Ai2));
+    assert_eq!(
+        result.reason,
+        Reason::InvalidOutput("I am not a move".to_string())
+    );
+}
+
+#[test]
+fn turn_timeout_counts_messages_sent_before_the_move() {
+    let read = |timeout: u64| {
+        let brain = MockBrain::new(row(0, 1))
+            .saying("thinking")
+            .slow(Duration::from_millis(60));
+        let mut ai = Ai::new(Box::new(brain));
+        ai.set_timeout(Some(Duration::from_millis(timeout)));
+        ai.write("BEGIN".to_string());
+        let location = ai.read_move();
+        (location, ai.take_messages())
+    };
+
+    // Each line comes within the timeout, but not the whole turn
+    assert_eq!(
+        read(100),
+        (Err(Fault::Timeout), vec!["MESSAGE thinking".to_string()])
+    );
+    assert_eq!(read(300).0, Ok((0, 0).into()));
+}
+
+#[test]
+fn hanging_brain_loses_on_time() {
+    let result = play(
+        MockBrain::new(row(0, 2)),
+        MockBrain::new(row(1, 1)).then(Misbehaviour::Hang),
+    );
+
+    assert_eq!(result.winner, Some(Cell::Ai1));
+    assert_eq!(result.reason, Reason::Timeout);
+}
+
+#[test]
+fn crashing_brain_loses() {
+    let result = play(
+        MockBrain::misbehaving(Misbehaviour::Crash),
+        MockBrain::new(row(1, 5)),
+    );
+
+    assert_eq!(result.winner, Some(Cell::Ai2));
+    assert_eq!(result.reason, Reason::Crash);
+    assert_eq!(result.moves, 0);
+}
+
+#[test]
+fn playing_on_a_stone_is_illegal() {
+    let result = play(
+        MockBrain::new(row(0, 5)),
+        MockBrain::misbehaving(Misbehaviour::IllegalMove),
+    );
+
+    assert_eq!(result.winner, Some(Cell::Ai1));
+    assert_eq!(result.reason, Reason::IllegalMove((0, 0).into()));
+}
+
+#[test]
+fn playing_outside_of_the_board_is_illegal() {
+    let result = play(
+        MockBrain::misbehaving(Misbehaviour::IllegalMove),
+        MockBrain::new(row(0, 5)),
+    );
+
+    assert_eq!(result.winner, Some(Cell::Ai2));
+    assert!(matches!(result.reason, Reason::IllegalMove(_)));
+}
+
+#[test]
+fn second_player_begins_when_asked() {
+    let brain2 = MockBrain::new(row(1, 5));
+    let received = brain2.received();
+    let result = Game::new(
+        Ai::new(Box::new(MockBrain::new(row(0, 5)))),
+        Ai::new(Box::new(brain2)),
+    )
+    .run(&GameSettings {
+        ai1_starting: false,
+        ..settings()
+    });
+
+    assert_eq!(result.winner, Some(Cell::Ai2));
+    assert_eq!(received.lock().unwrap()[2], "BEGIN");
+}
+
+fn four_in_a_row() -> TestCondition {
+    TestCondition::new(
+        vec![
+            ((2, 3).into(), Cell::Ai1),
+            ((3, 3).into(), Cell::Ai1),
+            ((4, 3).into(), Cell::Ai1),
+            ((6, 3).into(), Cell::Ai1),
+        ],
+        vec![(5, 3).into()],
+    )
+}
+
+fn run_test(brain: MockBrain) -> TestResult {
+    let mut test = Test::new(Ai::new(Box::new(brain)));
+    test.start_tests("mock", vec![four_in_a_row()])
+        .pop()
+        .unwrap()
+}
+
+#[test]
+fn expected_move_succeeds() {
+    let result = run_test(MockBrain::new(vec![(5, 3).into()]));
+
+    assert!(matches!(result, TestResult::Success(location) if location == (5, 3).into()));
+}
+
+#[test]
+fn unexpected_move_fails() {
+    let result = run_test(MockBrain::new(vec![(7, 3).into()]));
+
+    assert!(matches!(result, TestResult::Fail(location, _) if location == (7, 3).into()));
+}
+
+#[test]
+fn misbehaving_brain_is_an_error() {
+    for misbehaviour in [
+        Misbehaviour::Hang,
+        Misbehaviour::Crash,
+        Misbehaviour::Garbage,
+    ] {
+        let result = run_test(MockBrain::misbehaving(misbehaviour));
+
+        assert!(matches!(result, TestResult::Error), "{:?}", misbehaviour);
+    }
+}
+
+#[test]
+fn position_is_sent_as_board() {
+    let brain = MockBrain::new(vec![(5, 3).into()]);
+    let received = brain.received();
+    run_test(brain);
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![
+            "START 20",
+            "INFO timeout_turn 0",
+            "BOARD",
+            "2,3,1",
+            "3,3,1",
+            "4,3,1",
+            "6,3,1",
+            "DONE"
+        ]
+    );
+}
+
+#[test]
+fn owner_is_relative_to_side_to_move() {
+    let brain = MockBrain::new(vec![(5, 3).into()]);
+    let received = brain.received();
+    let mut test = TestCondition::new(
+        vec![
+            ((2, 3).into(), Cell::Ai1),
+            ((3, 3).into(), Cell::Ai2),
+            ((4, 3).into(), Cell::Ai1),
+        ],
+        vec![],
+    );
+    test.to_move = Cell::Ai2;
+    test.continuous = vec![(4, 3).into()];
+    Test::new(Ai::new(Box::new(brain))).start_tests("mock", vec![test]);
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![
+            "START 20",
+            "INFO timeout_turn 0",
+            "BOARD",
+            "2,3,2",
+            "3,3,1",
+            "4,3,3",
+            "DONE"
+        ]
+    );
+}
+
+#[test]
+fn hanging_past_time_limit_is_a_timeout() {
+    let mut test = four_in_a_row();
+    test.time_limit = Some(Duration::from_millis(100));
+    let result = Test::new(Ai::new(Box::new(MockBrain::misbehaving(
+        Misbehaviour::Hang,
+    ))))
+    .start_tests("mock", vec![test])
+    .pop()
+    .unwrap();
+
+    assert!(matches!(result, TestResult::Timeout(None)));
+}
+
+#[test]
+fn isolation_resets_the_ai_between_tests() {
+    for (isolation, between) in [
+        (Isolation::Start, "START 20"),
+        (Isolation::Restart, "RESTART"),
+        (Isolation::Continuous, "INFO timeout_turn 0"),
+    ] {
+        let brain = MockBrain::new(vec![(5, 3).into(), (5, 3).into()]);
+        let received = brain.received();
+        let mut test = Test::new(Ai::new(Box::new(brain)));
+        test.isolation = isolation;
+        test.start_tests("mock", vec![four_in_a_row(), four_in_a_row()]);
+
+        // START 20, INFO, BOARD, 4 stones, DONE for the first test
+        assert_eq!(received.lock().unwrap()[8], between, "{:?}", isolation);
+    }
+}
+
+#[test]
+fn time_limit_of_a_test_does_not_hold_for_the_next() {
+    for isolation in [Isolation::Start, Isolation::Continuous] {
+        let mut limited = four_in_a_row();
+        limited.time_limit = Some(Duration::from_millis(500));
+        let brain = MockBrain::new(vec![(5, 3).into(), (5, 3).into()]);
+        let received = brain.received();
+        let mut test = Test::new(Ai::new(Box::new(brain)));
+        test.isolation = isolation;
+        test.start_tests("mock", vec![limited, four_in_a_row()]);
+
+        let timeouts: Vec<String> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|line| line.starts_with("INFO timeout_turn"))
+            .cloned()
+            .collect();
+        assert_eq!(
+            timeouts,
+            ["INFO timeout_turn 500", "INFO timeout_turn 0"],
+            "{:?}",
+            isolation
+        );
+    }
+}
+
+#[test]
+fn late_answer_is_not_taken_for_the_next_test() {
+    for isolation in [Isolation::Start, Isolation::Restart, Isolation::Continuous] {
+        let mut timed = four_in_a_row();
+        timed.time_limit = Some(Duration::from_millis(100));
+        let brain = MockBrain::new(vec![(7, 3).into(), (5, 3).into(), (5, 3).into()]).late(1);
+        let mut test = Test::new(Ai::new(Box::new(brain)));
+        test.isolation = isolation;
+        let results = test.start_tests("mock", vec![timed, four_in_a_row(), four_in_a_row()]);
+
+        assert!(
+            matches!(results[0], TestResult::Timeout(None)),
+            "{:?}",
+            isolation
+        );
+        assert!(
+            matches!(results[1], TestResult::Success(location) if location == (5, 3).into()),
+            "{:?}: {:?}",
+            isolation,
+            results[1]
+        );
+        assert!(
+            matches!(results[2], TestResult::Success(_)),
+            "{:?}",
+            isolation
+        );
+    }
+}
+
+/// Brain server answering `5,3` to every position, for `connections` connections
+///
+/// Returns its address and the lines received on each connection.
+fn brain_server(connections: usize, hang_first: bool) -> (String, Arc<Mutex<Vec<Vec<String>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("tcp://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sessions = received.clone();
+    thread::spawn(move || {
+        let mut listener = Some(listener);
+        for connection in 0..connections {
+            let Ok((stream, _)) = listener.as_ref().unwrap().accept() else {
+                return;
+            };
+            // Further connections are refused
+            if connection + 1 == connections {
+                listener = None;
+            }
+            sessions.lock().unwrap().push(Vec::new());
+            let sessions = sessions.clone();
+            thread::spawn(move || {
+                let mut writer = stream.try_clone().unwrap();
+                for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                    sessions.lock().unwrap()[connection].push(line.clone());
+                    let answer = match line.as_str() {
+                        "END" => break,
+                        command if command.starts_with("START") => "OK",
+                        "DONE" if hang_first && connection == 0 => continue,
+                        "DONE" => "5,3",
+                        _ => continue,
+                    };
+                    let _ = writeln!(writer, "{}", answer);
+                }
+            });
+        }
+        drop(listener);
+    });
+    (address, received)
+}
+
+#[test]
+fn brain_timing_out_is_started_again() {
+    let (address, received) = brain_server(2, true);
+    let mut timed = four_in_a_row();
+    timed.time_limit = Some(Duration::from_millis(100));
+    let mut next = four_in_a_row();
+    next.time_limit = Some(Duration::from_secs(5));
+    let mut test = Test::init(&address).unwrap();
+    let results = test.start_tests("tcp", vec![timed, next]);
+
+    assert!(matches!(results[0], TestResult::Timeout(None)));
+    assert!(matches!(results[1], TestResult::Success(location) if location == (5, 3).into()));
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].last().unwrap(), "END");
+}
+
+#[test]
+fn process_isolation_starts_a_new_brain_for_every_test() {
+    let (address, received) = brain_server(3, false);
+    let mut test = Test::init(&address).unwrap();
+    test.isolation = Isolation::Process;
+    let results = test.start_tests(
+        "tcp",
+        vec![four_in_a_row(), four_in_a_row(), four_in_a_row()],
+    );
+
+    assert!(results
+        .iter()
+        .all(|result| matches!(result, TestResult::Success(_))));
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 3);
+    assert_eq!(received[0].first().unwrap(), "START 20");
+    assert_eq!(received[0].last().unwrap(), "END");
+    assert_eq!(received[1].first().unwrap(), "START 20");
+}
+
+#[test]
+fn brain_failing_to_start_again_is_kept() {
+    let (address, received) = brain_server(1, false);
+    let mut test = Test::init(&address).unwrap();
+    test.isolation = Isolation::Process;
+    let results = test.start_tests(
+        "tcp",
+        vec![four_in_a_row(), four_in_a_row(), four_in_a_row()],
+    );
+
+    assert!(matches!(results[0], TestResult::Success(_)));
+    assert!(matches!(results[1], TestResult::Error));
+    assert!(matches!(results[2], TestResult::Error));
+    // The first brain was neither stopped nor played with
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    assert!(!received[0].contains(&"END".to_string()));
+    assert_eq!(
+        received[0].iter().filter(|line| *line == "BOARD").count(),
+        1
+    );
+}
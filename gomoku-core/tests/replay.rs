@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use gomoku_core::{board::Cell, location::Location, record::GameRecord, replay::Replay};
+
+fn replay(moves: usize) -> Replay {
+    let mut record = GameRecord::new(15, ["black".to_string(), "white".to_string()]);
+    for x in 0..moves {
+        record.push((x, 7).into(), Duration::from_millis(10));
+    }
+    Replay::new(record, Duration::from_millis(500))
+}
+
+#[test]
+fn stepping_stays_within_the_game() {
+    let mut replay = replay(3);
+
+    replay.previous();
+    assert_eq!(replay.position, 0);
+    replay.next();
+    replay.next();
+    assert_eq!(replay.moves(), [Location::from((0, 7)), (1, 7).into()]);
+    assert_eq!(replay.board().get(1, 7), Cell::Ai2);
+    replay.next();
+    replay.next();
+    assert_eq!(replay.position, 3);
+    replay.previous();
+    assert_eq!(replay.position, 2);
+    replay.start();
+    assert_eq!(replay.board().get(0, 7), Cell::Empty);
+    replay.end();
+    assert_eq!(replay.position, replay.len());
+}
+
+#[test]
+fn auto_play_stops_at_the_end() {
+    let mut replay = replay(2);
+
+    replay.tick();
+    assert_eq!(replay.position, 0);
+    replay.toggle_play();
+    replay.tick();
+    assert!(replay.playing);
+    replay.tick();
+    assert_eq!(replay.position, 2);
+    assert!(!replay.playing);
+    replay.tick();
+    assert_eq!(replay.position, 2);
+}
+
+#[test]
+fn playing_again_from_the_end_starts_over() {
+    let mut replay = replay(2);
+    replay.end();
+
+    replay.toggle_play();
+    assert!(replay.playing);
+    assert_eq!(replay.position, 0);
+    replay.toggle_play();
+    assert!(!replay.playing);
+    assert_eq!(replay.position, 0);
+}
+
+#[test]
+fn stepping_back_pauses_the_auto_play() {
+    let mut replay = replay(3);
+    replay.toggle_play();
+    replay.tick();
+
+    replay.previous();
+    assert!(!replay.playing);
+    assert_eq!(replay.position, 0);
+}
+
+#[test]
+fn speed_is_kept_within_bounds() {
+    let mut replay = replay(0);
+
+    assert!(replay.is_empty());
+    replay.faster();
+    assert_eq!(replay.interval, Duration::from_millis(250));
+    for _ in 0..10 {
+        replay.faster();
+    }
+    assert_eq!(replay.interval, Duration::from_millis(50));
+    for _ in 0..10 {
+        replay.slower();
+    }
+    assert_eq!(replay.interval, Duration::from_secs(8));
+}
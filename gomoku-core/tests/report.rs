@@ -0,0 +1,211 @@
+use std::{path::Path, time::Duration};
+
+use gomoku_core::{
+    board::{Board, Cell},
+    report::{suffixed, Report, Status, TestReport},
+    test::{TestCondition, TestResult},
+};
+
+fn condition(id: &str) -> TestCondition {
+    let mut test = TestCondition::new(
+        vec![
+            ((3, 3).into(), Cell::Ai1),
+            ((4, 3).into(), Cell::Ai1),
+            ((3, 4).into(), Cell::Ai2),
+        ],
+        vec![(5, 3).into(), (2, 3).into()],
+    );
+    test.id = id.to_string();
+    test.board_size = 15;
+    test.forbidden_moves = vec![(9, 9).into()];
+    test
+}
+
+fn test_report(suite: &str, id: &str, result: TestResult, time_ms: u64) -> TestReport {
+    TestReport::new(
+        suite,
+        &condition(id),
+        &result,
+        Duration::from_millis(time_ms),
+    )
+}
+
+fn run() -> Report {
+    let mut report = Report::default();
+    report.push(test_report(
+        "attack",
+        "four",
+        TestResult::Success((5, 3).into()),
+        12,
+    ));
+    report.push(test_report(
+        "attack",
+        "a<b>&\"c\"",
+        TestResult::Fail((9, 9).into(), Board::new(15)),
+        250,
+    ));
+    report.push(test_report(
+        "defence",
+        "slow",
+        TestResult::Timeout(Some((5, 3).into())),
+        1500,
+    ));
+    report.push(test_report("defence", "dead", TestResult::Error, 3));
+    report
+}
+
+#[test]
+fn test_report_describes_the_position_and_outcome() {
+    let report = run();
+    let failed = &report.tests[1];
+
+    assert_eq!(failed.position.board_size, 15);
+    assert_eq!(failed.position.black, ["3,3", "4,3"]);
+    assert_eq!(failed.position.white, ["3,4"]);
+    assert_eq!(failed.position.to_move, "black");
+    assert_eq!(failed.expected, ["5,3", "2,3"]);
+    assert_eq!(failed.forbidden, ["9,9"]);
+    assert_eq!(failed.actual.as_deref(), Some("9,9"));
+    assert_eq!(failed.status, Status::Failed);
+    assert_eq!(failed.outcome(), "failed (9,9)");
+    assert_eq!(report.tests[3].actual, None);
+    assert_eq!(report.tests[3].outcome(), "error");
+}
+
+#[test]
+fn json_report_lists_every_test() {
+    let json: serde_json::Value = serde_json::from_str(&run().to_json().unwrap()).unwrap();
+    let tests = json["tests"].as_array().unwrap();
+
+    assert_eq!(tests.len(), 4);
+    assert_eq!(tests[0]["suite"], "attack");
+    assert_eq!(tests[0]["id"], "four");
+    assert_eq!(tests[0]["status"], "passed");
+    assert_eq!(tests[0]["actual"], "5,3");
+    assert_eq!(tests[0]["time_ms"], 12.0);
+    assert_eq!(tests[0]["position"]["black"][1], "4,3");
+    assert_eq!(tests[0]["position"]["to_move"], "black");
+    assert_eq!(tests[1]["status"], "failed");
+    assert_eq!(tests[2]["status"], "timeout");
+    assert_eq!(tests[2]["actual"], "5,3");
+    assert_eq!(tests[3]["status"], "error");
+    assert!(tests[3]["actual"].is_null());
+}
+
+#[test]
+fn junit_report_groups_tests_by_suite() {
+    let xml = run().to_junit();
+    let document = roxmltree::Document::parse(&xml).unwrap();
+    let root = document.root_element();
+    let suites: Vec<_> = root
+        .children()
+        .filter(|node| node.has_tag_name("testsuite"))
+        .collect();
+
+    assert_eq!(root.attribute("tests"), Some("4"));
+    assert_eq!(root.attribute("failures"), Some("2"));
+    assert_eq!(root.attribute("errors"), Some("1"));
+    assert_eq!(root.attribute("time"), Some("1.765"));
+    assert_eq!(suites.len(), 2);
+    assert_eq!(suites[0].attribute("name"), Some("attack"));
+    assert_eq!(suites[0].attribute("failures"), Some("1"));
+    assert_eq!(suites[1].attribute("errors"), Some("1"));
+
+    let cases: Vec<_> = document
+        .descendants()
+        .filter(|node| node.has_tag_name("testcase"))
+        .collect();
+    assert_eq!(cases[0].attribute("name"), Some("four"));
+    assert!(cases[0].first_element_child().is_none());
+    // Names are escaped, the document being well formed proves it
+    assert_eq!(cases[1].attribute("name"), Some("a<b>&\"c\""));
+    let failure = cases[1].first_element_child().unwrap();
+    assert!(failure.has_tag_name("failure"));
+    assert_eq!(
+        failure.attribute("message"),
+        Some("played 9,9, expected [5,3 2,3], forbidden [9,9]")
+    );
+    let timeout = cases[2].first_element_child().unwrap();
+    assert_eq!(timeout.attribute("type"), Some("timeout"));
+    assert!(cases[3]
+        .first_element_child()
+        .unwrap()
+        .has_tag_name("error"));
+}
+
+fn statuses(tests: Vec<(&str, TestResult, u64)>) -> Report {
+    let mut report = Report::default();
+    for (id, result, time_ms) in tests {
+        report.push(test_report("suite", id, result, time_ms));
+    }
+    report
+}
+
+fn ids(changes: &[gomoku_core::report::Change]) -> Vec<String> {
+    changes.iter().map(|change| change.new.id.clone()).collect()
+}
+
+#[test]
+fn comparison_classifies_changes_between_builds() {
+    let passed = || TestResult::Success((5, 3).into());
+    let failed = || TestResult::Fail((9, 9).into(), Board::new(15));
+    let old = statuses(vec![
+        ("kept", passed(), 100),
+        ("broken", passed(), 100),
+        ("timed-out", passed(), 100),
+        ("fixed", failed(), 100),
+        ("still-failing", TestResult::Error, 100),
+        ("slower", passed(), 100),
+        ("faster", passed(), 100),
+        ("jitter", passed(), 5),
+        ("removed", passed(), 100),
+    ]);
+    let new = statuses(vec![
+        ("kept", passed(), 120),
+        ("broken", failed(), 100),
+        ("timed-out", TestResult::Timeout(None), 100),
+        ("fixed", passed(), 100),
+        ("still-failing", failed(), 100),
+        ("slower", passed(), 150),
+        ("faster", passed(), 40),
+        ("jitter", passed(), 12),
+        ("added", passed(), 100),
+    ]);
+    let comparison = new.compare(&old);
+
+    assert_eq!(ids(&comparison.regressions), ["broken", "timed-out"]);
+    assert_eq!(ids(&comparison.fixes), ["fixed"]);
+    assert_eq!(ids(&comparison.slower), ["slower"]);
+    assert_eq!(ids(&comparison.faster), ["faster"]);
+    let unmatched: Vec<&str> = comparison
+        .unmatched
+        .iter()
+        .map(|test| test.id.as_str())
+        .collect();
+    assert_eq!(unmatched, ["added", "removed"]);
+}
+
+#[test]
+fn only_regressions_fail_the_comparison() {
+    let passed = || TestResult::Success((5, 3).into());
+    let failed = || TestResult::Fail((9, 9).into(), Board::new(15));
+    let old = statuses(vec![("a", passed(), 10), ("b", failed(), 10)]);
+    let improved = statuses(vec![
+        ("a", passed(), 500),
+        ("b", passed(), 10),
+        ("c", failed(), 10),
+    ]);
+    let regressed = statuses(vec![("a", failed(), 10), ("b", passed(), 10)]);
+
+    assert!(!improved.compare(&old).has_regressions());
+    assert!(regressed.compare(&old).has_regressions());
+}
+
+#[test]
+fn suffix_goes_before_the_extension() {
+    assert_eq!(
+        suffixed(Path::new("out/report.json"), "old"),
+        Path::new("out/report-old.json")
+    );
+    assert_eq!(suffixed(Path::new("games"), "3"), Path::new("games-3"));
+}
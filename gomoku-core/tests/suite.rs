@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use gomoku_core::{board::Cell, suite::Suite};
+
+fn load_error(test: &str) -> String {
+    let toml = format!("board_size = 15\n\n[[tests]]\nid = \"typo\"\n{}", test);
+    match Suite::from_toml("bounds", &toml) {
+        Ok(_) => panic!("suite loaded with [{}]", test),
+        Err(err) => err,
+    }
+}
+
+#[test]
+fn bundled_suites_load() {
+    let suites = Path::new(env!("CARGO_MANIFEST_DIR")).join("../suites");
+    let suites = Suite::load_all(&suites).unwrap();
+
+    assert!(!suites.is_empty());
+    assert!(suites.iter().all(|suite| !suite.tests.is_empty()));
+}
+
+#[test]
+fn stones_outside_of_the_board_are_rejected() {
+    let err = load_error("own = [\"3,3\", \"15,3\"]\nexpected = [\"4,3\"]");
+
+    assert_eq!(err, "test typo: 15,3 is outside of the board");
+}
+
+#[test]
+fn expected_and_forbidden_moves_outside_of_the_board_are_rejected() {
+    let expected = load_error("own = [\"3,3\"]\nexpected = [\"4,30\"]");
+    let forbidden = load_error("own = [\"3,3\"]\nforbidden = [\"40,3\"]");
+
+    assert_eq!(expected, "test typo: 4,30 is outside of the board");
+    assert_eq!(forbidden, "test typo: 40,3 is outside of the board");
+}
+
+#[test]
+fn scored_moves_outside_of_the_board_are_rejected() {
+    let err = load_error("own = [\"3,3\"]\nscores = { \"4,3\" = \"best\", \"3,15\" = 5 }");
+
+    assert_eq!(err, "test typo: 3,15 is outside of the board");
+}
+
+#[test]
+fn unknown_scores_are_rejected() {
+    let err = load_error("own = [\"3,3\"]\nscores = { \"4,3\" = \"brilliant\" }");
+
+    assert_eq!(err, "test typo: unknown score [brilliant]");
+}
+
+#[test]
+fn suite_positions_can_be_algebraic() {
+    let toml = r#"
+        board_size = 15
+        [[tests]]
+        black = ["h8", "i8", "j8", "k8"]
+        white = ["h9", "i9", "j9"]
+        to_move = "black"
+        expected = ["g8", "12,7"]
+    "#;
+    let test = &Suite::from_toml("algebraic", toml).unwrap().tests[0];
+
+    assert!(test.board().contains(&((8, 7).into(), Cell::Ai1)));
+    assert_eq!(test.expected_moves(), [(6, 7).into(), (12, 7).into()]);
+}
@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+
+use gomoku_core::{
+    board::Cell,
+    location::Location,
+    suite::Suite,
+    symmetry::{self, Symmetry},
+    test::{self, TestCondition, TestOptions},
+};
+
+fn every_location(board_size: usize) -> Vec<Location> {
+    (0..board_size)
+        .flat_map(|y| (0..board_size).map(move |x| Location { x, y }))
+        .collect()
+}
+
+#[test]
+fn orientations_map_a_corner_where_expected() {
+    let corner = Location { x: 0, y: 1 };
+    let mapped: Vec<(usize, usize)> = Symmetry::ALL
+        .iter()
+        .map(|symmetry| symmetry.apply(corner, 15))
+        .map(|location| (location.x, location.y))
+        .collect();
+
+    assert_eq!(
+        mapped,
+        [
+            (0, 1),
+            (13, 0),
+            (14, 13),
+            (1, 14),
+            (14, 1),
+            (0, 13),
+            (1, 0),
+            (13, 14)
+        ]
+    );
+}
+
+#[test]
+fn orientations_are_permutations_of_the_board() {
+    for symmetry in Symmetry::ALL {
+        let mapped: HashSet<(usize, usize)> = every_location(7)
+            .into_iter()
+            .map(|location| symmetry.apply(location, 7))
+            .map(|location| (location.x, location.y))
+            .collect();
+
+        assert_eq!(mapped.len(), 49, "{}", symmetry);
+        assert!(mapped.iter().all(|&(x, y)| x < 7 && y < 7), "{}", symmetry);
+    }
+}
+
+#[test]
+fn orientations_are_distinct_and_rotations_compose() {
+    let location = Location { x: 2, y: 5 };
+    let images: HashSet<(usize, usize)> = Symmetry::ALL
+        .iter()
+        .map(|symmetry| symmetry.apply(location, 15))
+        .map(|location| (location.x, location.y))
+        .collect();
+    let mut rotated = location;
+    for _ in 0..4 {
+        rotated = Symmetry::Rotate90.apply(rotated, 15);
+    }
+
+    assert_eq!(images.len(), 8);
+    assert_eq!(rotated, location);
+    assert_eq!(
+        Symmetry::Rotate90.apply(Symmetry::Rotate90.apply(location, 15), 15),
+        Symmetry::Rotate180.apply(location, 15)
+    );
+}
+
+#[test]
+fn positions_near_an_edge_are_not_translated() {
+    let locations = [Location { x: 2, y: 9 }, Location { x: 7, y: 9 }];
+
+    assert_eq!(
+        symmetry::translations(&locations, 20),
+        [(0, -4), (0, 0), (0, 5)]
+    );
+}
+
+#[test]
+fn translations_reach_both_margins() {
+    let locations = [Location { x: 8, y: 9 }, Location { x: 10, y: 9 }];
+    let offsets = symmetry::translations(&locations, 20);
+
+    assert_eq!(offsets.len(), 9);
+    assert!(offsets.contains(&(0, 0)));
+    assert!(offsets.contains(&(-3, -4)));
+    assert!(offsets.contains(&(4, 5)));
+    for (dx, dy) in offsets {
+        for location in locations {
+            let moved = symmetry::translate(location, (dx, dy));
+            assert!((5..=14).contains(&moved.x) && (5..=14).contains(&moved.y));
+        }
+    }
+}
+
+#[test]
+fn symmetries_and_translations_are_independent() {
+    let mut test = TestCondition::new(
+        vec![((9, 9).into(), Cell::Ai1), ((10, 9).into(), Cell::Ai2)],
+        vec![(8, 9).into()],
+    );
+    test.id = "center".to_string();
+    let suite = || Suite {
+        name: "augmented".to_string(),
+        board_size: 20,
+        tests: vec![test.clone()],
+    };
+    let count = |symmetries: bool, translations: bool| {
+        let options = TestOptions {
+            symmetries,
+            translations,
+            ..TestOptions::default()
+        };
+        test::select(vec![suite()], &options)[0].tests.len()
+    };
+    let translated = test::select(
+        vec![suite()],
+        &TestOptions {
+            translations: true,
+            ..TestOptions::default()
+        },
+    );
+
+    assert_eq!(count(false, false), 1);
+    assert_eq!(count(true, false), 8);
+    assert_eq!(count(false, true), 9);
+    assert_eq!(count(true, true), 72);
+    assert!(translated[0]
+        .tests
+        .iter()
+        .all(|test| test.orientation == Symmetry::Identity));
+}
@@ -0,0 +1,153 @@
+use std::sync::{mpsc, Arc, Mutex};
+
+use gomoku_core::{
+    ai::Ai,
+    board::Cell,
+    game::{GameResult, GameSettings, Reason},
+    mock::MockBrain,
+    tournament::{self, MatchResult, Tournament, TournamentEvent, TournamentSettings},
+};
+
+fn result(pairing: usize, winner: Option<Cell>, reason: Reason) -> MatchResult {
+    MatchResult {
+        pairing: Tournament::pairings(3, 2)[pairing],
+        result: GameResult::new(winner, reason),
+    }
+}
+
+#[test]
+fn pairings_alternate_the_first_brain() {
+    let pairings = Tournament::pairings(3, 2);
+
+    assert_eq!(pairings.len(), 6);
+    assert_eq!((pairings[0].first, pairings[0].second), (0, 1));
+    assert_eq!((pairings[3].first, pairings[3].second), (1, 0));
+    assert_eq!(pairings[5].game, 5);
+}
+
+#[test]
+fn standings_count_points_and_faults() {
+    let mut tournament = Tournament::new(vec!["a".into(), "b".into(), "c".into()]);
+    // a-b, a-c, b-c, then b-a, c-a, c-b
+    tournament.push(result(0, Some(Cell::Ai1), Reason::Five));
+    tournament.push(result(1, Some(Cell::Ai1), Reason::Timeout));
+    tournament.push(result(2, None, Reason::BoardFull));
+    tournament.push(result(3, Some(Cell::Ai2), Reason::Crash));
+
+    let standings = tournament.standings();
+    assert_eq!(standings[0].brain, 0);
+    assert_eq!(standings[0].score, 3.0);
+    assert_eq!(standings[0].elo, 800.0);
+    assert_eq!((standings[1].brain, standings[1].crashes), (1, 1));
+    assert_eq!(standings[1].draws, 1);
+    assert_eq!((standings[2].brain, standings[2].timeouts), (2, 1));
+    assert_eq!(tournament.crosstable(1, 0), Some(0.0));
+    assert_eq!(tournament.crosstable(1, 2), Some(0.5));
+    assert_eq!(tournament.crosstable(2, 2), None);
+}
+
+#[test]
+fn elo_is_even_for_half_the_points() {
+    assert_eq!(tournament::elo(2.0, 4), 0.0);
+    assert!((tournament::elo(3.0, 4) - 190.8).abs() < 0.1);
+    assert_eq!(tournament::elo(0.0, 0), 0.0);
+}
+
+/// Mock brain of `spec`: `five` plays a row, `hang` never answers and anything else can't start
+fn mock_brain(spec: &str) -> Result<Ai, String> {
+    let brain = match spec {
+        "five" => MockBrain::new((0..5).map(|x| (x, 0).into()).collect()),
+        "hang" => MockBrain::new(Vec::new()),
+        _ => return Err(format!("no brain at [{}]", spec)),
+    };
+    Ok(Ai::new(Box::new(brain)).named(spec))
+}
+
+#[test]
+fn tournament_plays_every_pairing() {
+    let settings = TournamentSettings {
+        brains: vec!["five".into(), "hang".into(), "missing".into()],
+        rounds: 2,
+        concurrency: 2,
+        game: GameSettings {
+            board_size: 15,
+            ai1_starting: true,
+            timeout_turn: None,
+            trace: None,
+            record: None,
+            opening: Vec::new(),
+        },
+    };
+    let (events, received) = mpsc::channel();
+    let tournament = Tournament::run_with(&settings, events, mock_brain);
+    let events: Vec<TournamentEvent> = received.into_iter().collect();
+    let standings = tournament.standings();
+
+    assert_eq!(tournament.results.len(), 6);
+    assert_eq!((standings[0].brain, standings[0].score), (0, 4.0));
+    assert_eq!((standings[1].brain, standings[1].score), (1, 2.0));
+    assert_eq!(standings[1].timeouts, 2);
+    assert_eq!((standings[2].brain, standings[2].crashes), (2, 4));
+    let ended = events
+        .iter()
+        .filter(|event| matches!(event, TournamentEvent::GameEnded(_)))
+        .count();
+    assert_eq!(ended, 6);
+    assert!(matches!(events.last(), Some(TournamentEvent::Finished)));
+}
+
+#[test]
+fn brains_failing_to_start_lose() {
+    let settings = TournamentSettings {
+        brains: vec!["five".into(), "missing".into(), "gone".into()],
+        rounds: 1,
+        concurrency: 1,
+        game: GameSettings {
+            board_size: 15,
+            ai1_starting: true,
+            timeout_turn: None,
+            trace: None,
+            record: None,
+            opening: Vec::new(),
+        },
+    };
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let sessions = started.clone();
+    let start = move |spec: &str| {
+        let brain = MockBrain::new((0..5).map(|x| (x, 0).into()).collect());
+        sessions.lock().unwrap().push(brain.received());
+        match spec {
+            "five" => Ok(Ai::new(Box::new(brain))),
+            _ => Err(format!("no brain at [{}]", spec)),
+        }
+    };
+    let (events, _received) = mpsc::channel();
+    let tournament = Tournament::run_with(&settings, events, start);
+    let standings = tournament.standings();
+
+    assert_eq!((standings[0].brain, standings[0].score), (0, 2.0));
+    // missing-gone is lost by both rather than drawn
+    for standing in &standings[1..] {
+        assert_eq!((standing.score, standing.draws), (0.0, 0), "{:?}", standing);
+        assert_eq!(
+            (standing.losses, standing.crashes),
+            (2, 2),
+            "{:?}",
+            standing
+        );
+    }
+    assert_eq!(tournament.crosstable(1, 2), Some(0.0));
+    // The brain which started is stopped with its opponent missing
+    let started = started.lock().unwrap();
+    let five: Vec<_> = started
+        .iter()
+        .filter(|received| !received.lock().unwrap().is_empty())
+        .collect();
+    assert_eq!(five.len(), 2);
+    for received in five {
+        assert_eq!(
+            received.lock().unwrap().last().map(String::as_str),
+            Some("END")
+        );
+    }
+}
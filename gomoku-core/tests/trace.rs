@@ -0,0 +1,127 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use gomoku_core::{
+    ai::Ai,
+    game::{Game, GameSettings},
+    location::Location,
+    mock::MockBrain,
+    trace::{self, Direction, Trace, TraceEntry},
+};
+
+fn row(y: usize, count: usize) -> Vec<Location> {
+    (0..count).map(|x| (x, y).into()).collect()
+}
+
+fn settings(timeout: Option<u64>, opening: Vec<Location>) -> GameSettings {
+    GameSettings {
+        board_size: 15,
+        ai1_starting: true,
+        timeout_turn: timeout.map(Duration::from_millis),
+        trace: None,
+        record: None,
+        opening,
+    }
+}
+
+fn trace_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("gomoku-{}-{}.trace", name, std::process::id()))
+}
+
+/// Trace of a game between two mock brains named `name1` and `name2`
+fn traced_game(name: &str, name1: &str, name2: &str, settings: GameSettings) -> PathBuf {
+    let path = trace_path(name);
+    let mut game = Game::new(
+        Ai::new(Box::new(MockBrain::new(row(0, 5)))).named(name1),
+        Ai::new(Box::new(MockBrain::new(row(1, 5)))).named(name2),
+    );
+    game.run(&GameSettings {
+        trace: Some(path.to_string_lossy().to_string()),
+        ..settings
+    });
+    path
+}
+
+#[test]
+fn trace_entry_round_trips_with_escaped_names() {
+    let entry = TraceEntry {
+        elapsed: Duration::from_micros(1234),
+        direction: Direction::Recv,
+        brain: "C:\\brains\\my\tbrain".to_string(),
+        line: "7,7".to_string(),
+    };
+    let line = entry.to_string();
+    let parsed = TraceEntry::from_string(&line).unwrap();
+
+    assert_eq!(line.split('\t').count(), 4);
+    assert_eq!(parsed.elapsed, entry.elapsed);
+    assert_eq!(parsed.direction, Direction::Recv);
+    assert_eq!(parsed.brain, entry.brain);
+    assert_eq!(parsed.line, "7,7");
+}
+
+#[test]
+fn version_1_names_are_read_verbatim() {
+    let entry = TraceEntry::parse("10\t->\tC:\\brains\\new.exe\tBEGIN", 1).unwrap();
+
+    assert_eq!(entry.brain, "C:\\brains\\new.exe");
+    assert_eq!(entry.line, "BEGIN");
+}
+
+#[test]
+fn traced_game_replays_identically() {
+    let path = traced_game(
+        "identical",
+        "alpha\tbeta",
+        "gamma",
+        settings(Some(500), vec![(7, 7).into()]),
+    );
+    let entries = Trace::load(&path.to_string_lossy()).unwrap();
+    let divergences = trace::replay(&path.to_string_lossy()).unwrap();
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(entries[0].line, format!("TRACE {}", trace::TRACE_VERSION));
+    assert!(entries.iter().any(|entry| entry.line == "OPENING 7,7"));
+    assert!(entries.iter().any(|entry| entry.brain == "alpha\tbeta"));
+    assert_eq!(divergences, Vec::<String>::new());
+}
+
+#[test]
+fn version_1_trace_replays() {
+    let path = traced_game("version-1", "alpha", "gamma", settings(None, Vec::new()));
+    let content = fs::read_to_string(&path).unwrap();
+    // Version 1 had no header and no turn timeout in its settings
+    let lines: Vec<String> = content
+        .lines()
+        .skip(1)
+        .map(|line| line.replace("SETTINGS 15 1 0", "SETTINGS 15 1"))
+        .collect();
+    assert!(lines.iter().any(|line| line.ends_with("SETTINGS 15 1")));
+    fs::write(&path, lines.join("\n")).unwrap();
+    let divergences = trace::replay(&path.to_string_lossy()).unwrap();
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(divergences, Vec::<String>::new());
+}
+
+#[test]
+fn replay_reports_where_the_referee_diverged() {
+    let path = traced_game("diverged", "alpha", "gamma", settings(None, Vec::new()));
+    let content = fs::read_to_string(&path).unwrap();
+    fs::write(&path, content.replace("TURN 0,0", "TURN 9,9")).unwrap();
+    let divergences = trace::replay(&path.to_string_lossy()).unwrap();
+    let _ = fs::remove_file(&path);
+
+    assert!(divergences
+        .iter()
+        .any(|divergence| divergence.contains("sent [TURN 0,0] instead of [TURN 9,9]")));
+}
+
+#[test]
+fn newer_trace_versions_are_refused() {
+    let path = trace_path("newer");
+    fs::write(&path, "0\t##\treferee\tTRACE 99\n").unwrap();
+    let loaded = Trace::load(&path.to_string_lossy());
+    let _ = fs::remove_file(&path);
+
+    assert!(loaded.is_err());
+}
@@ -0,0 +1,96 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    thread,
+    time::Duration,
+};
+
+use gomoku_core::{
+    ai::{Ai, Fault},
+    location::Location,
+};
+
+/// Brain server answering `OK` to `START` and `10,10` to `BEGIN`, for one connection
+fn serve<S: std::io::Read + Write>(stream: S) {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while reader.read_line(&mut line).unwrap_or(0) > 0 {
+        let answer = match line.trim_end() {
+            command if command.starts_with("START") => Some("OK"),
+            "BEGIN" => Some("10,10"),
+            _ => None,
+        };
+        if let Some(answer) = answer {
+            let stream = reader.get_mut();
+            writeln!(stream, "{}", answer).unwrap();
+            stream.flush().unwrap();
+        }
+        line.clear();
+    }
+}
+
+fn begin(ai: &mut Ai) -> Result<Location, Fault> {
+    ai.reset(20);
+    ai.write("BEGIN".to_string());
+    ai.flush();
+    ai.read_move()
+}
+
+#[test]
+fn tcp_brain_is_played_through_the_socket() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = thread::spawn(move || serve(listener.accept().unwrap().0));
+
+    let mut ai = Ai::from_spec(&format!("tcp://{}", address)).unwrap();
+
+    assert_eq!(begin(&mut ai), Ok((10, 10).into()));
+    ai.stop();
+    server.join().unwrap();
+}
+
+#[test]
+fn tcp_brain_not_answering_times_out() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap_or(0) > 0 {}
+    });
+
+    let mut ai = Ai::from_spec(&format!("tcp://{}", address)).unwrap();
+    ai.set_timeout(Some(Duration::from_millis(50)));
+
+    assert_eq!(ai.read_move(), Err(Fault::Timeout));
+    ai.stop();
+    server.join().unwrap();
+}
+
+#[test]
+fn unreachable_server_is_an_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+
+    assert!(Ai::from_spec(&format!("tcp://{}", address)).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_brain_is_played_through_the_socket() {
+    use std::os::unix::net::UnixListener;
+
+    let path = std::env::temp_dir().join(format!("gomoku-transport-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    let server = thread::spawn(move || serve(listener.accept().unwrap().0));
+
+    let mut ai = Ai::from_spec(&format!("unix://{}", path.display())).unwrap();
+
+    assert_eq!(begin(&mut ai), Ok((10, 10).into()));
+    ai.stop();
+    server.join().unwrap();
+    let _ = std::fs::remove_file(&path);
+}
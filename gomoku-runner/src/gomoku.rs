@@ -1,12 +1,32 @@
-use std::env;
+use std::{env, path::Path, time::Duration};
 
-use gomoku_ui::Gomoku;
+use gomoku_ui::{Fight, Gomoku, DEFAULT_INTERVAL};
 
-use gomoku_core::test::Test;
+use gomoku_core::{
+    filter::{self, Filter},
+    game::GameSettings,
+    location::{Location, Notation},
+    player::HUMAN,
+    record::GameRecord,
+    report::suffixed,
+    suite::Suite,
+    test::{self, Isolation, Test, TestOptions},
+    tournament::TournamentSettings,
+    trace,
+};
+
+const DEFAULT_SUITES: &str = "suites";
+const DEFAULT_BOARD_SIZE: usize = 20;
+/// Games of each pair of brains in a tournament, each starting once
+const DEFAULT_ROUNDS: usize = 2;
 
 enum StartMode {
-    Fight,
-    Test(String),
+    Fight(Option<Fight>),
+    Test(String, Vec<String>, TestOptions),
+    Compare(String, String, Vec<String>, TestOptions),
+    ReplayTrace(String),
+    View(String, Duration),
+    Tournament(TournamentSettings),
     Unknown,
     Incorrect,
 }
@@ -19,22 +39,107 @@ impl StartMode {
                 panic!("Unknown mode");
             }
             Self::Incorrect => {}
-            Self::Fight => {
+            Self::Fight(None) => {
                 println!("Running in fight mode!");
                 Gomoku::run();
-                //Game::init(ai1_path, ai2_path)
-                //    .expect("")
-                //    .run(&GameSettings {
-                //        board_size: 20,
-                //        ai1_starting: true,
-                //    });
-            }
-            Self::Test(ai_path) => {
+            }
+            Self::Fight(Some(fight)) => {
+                println!("Running in fight mode!");
+                Gomoku::fight(fight.clone());
+            }
+            Self::Test(_, suite_paths, options) if options.list => {
+                list_tests(load_suites(suite_paths), options);
+            }
+            Self::Compare(_, _, suite_paths, options) if options.list => {
+                list_tests(load_suites(suite_paths), options);
+            }
+            Self::Test(ai_path, suite_paths, options) => {
+                let suites = load_suites(suite_paths);
+
                 println!("Running in test mode!");
-                Test::init(ai_path).expect("").run();
+                Test::init(ai_path).expect("").run(suites, options);
+            }
+            Self::Compare(old_path, new_path, suite_paths, options) => {
+                let suites = load_suites(suite_paths);
+
+                println!("Running in compare mode!");
+                let mut reports = Vec::new();
+                for (build, ai_path) in [("old", old_path), ("new", new_path)] {
+                    println!("Testing {}", ai_path);
+                    // Each build gets its own reports
+                    let options = TestOptions {
+                        json: options.json.as_deref().map(|path| suffixed(path, build)),
+                        junit: options.junit.as_deref().map(|path| suffixed(path, build)),
+                        ..options.clone()
+                    };
+                    let mut test = Test::init(ai_path).expect("");
+                    test.run(suites.clone(), &options);
+                    reports.push(test.into_report());
+                }
+
+                let comparison = reports[1].compare(&reports[0]);
+                comparison.display();
+                if comparison.has_regressions() {
+                    std::process::exit(1);
+                }
+            }
+            Self::Tournament(settings) => {
+                println!("Running in tournament mode!");
+                Gomoku::tournament(settings.clone());
+            }
+            Self::View(record_path, interval) => {
+                let record = match GameRecord::load(Path::new(record_path)) {
+                    Ok(record) => record,
+                    Err(err) => {
+                        eprintln!("Error whilst loading game record: [{}]", err);
+                        std::process::exit(1);
+                    }
+                };
+                println!("Viewing {}!", record_path);
+                Gomoku::replay(record, *interval);
+            }
+            Self::ReplayTrace(trace_path) => {
+                println!("Replaying trace {}!", trace_path);
+                match trace::replay(trace_path) {
+                    Ok(divergences) if divergences.is_empty() => {
+                        println!("The referee behaved deterministically");
+                    }
+                    Ok(divergences) => {
+                        for divergence in divergences {
+                            println!("{}", divergence);
+                        }
+                        std::process::exit(1);
+                    }
+                    Err(err) => {
+                        eprintln!("Error whilst replaying trace: [{}]", err);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn load_suites(paths: &[String]) -> Vec<Suite> {
+    let mut suites = Vec::new();
+    for path in paths {
+        match Suite::load_all(Path::new(path)) {
+            Ok(loaded) => suites.extend(loaded),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
             }
         }
     }
+    suites
+}
+
+fn list_tests(suites: Vec<Suite>, options: &TestOptions) {
+    for suite in test::select(suites, options) {
+        for test in suite.tests {
+            println!("{}", filter::test_name(&suite.name, &test.id));
+        }
+    }
 }
 
 fn show_help() {
@@ -46,8 +151,38 @@ fn show_help() {
         "Usage: gomoku_player [COMMAND] [ARGS]",
         "",
         "Commands:",
-        "\t--test <AI_PATH>\t\tRun functionnal tests for <AI_PATH>",
-        "\t--fight\t\tRun the fight ui",
+        "\t--test <AI_PATH> [OPTIONS] [SUITE_PATH...]\t\tRun functionnal tests for <AI_PATH>",
+        "\t\t\t\t\t\tfrom suite files or directories (./suites by default)",
+        "\t\t--symmetries\t\tAlso run every test in the 8 orientations of the board",
+        "\t\t--translations\t\tAlso run every test (every orientation with --symmetries) moved across the board",
+        "\t\t--filter <PATTERN>\t\tOnly run tests whose <SUITE>/<TEST_ID> matches the glob <PATTERN>",
+        "\t\t\t\t\t\tor the regex following `re:`, can be repeated",
+        "\t\t--list\t\tPrint the selected tests without running them",
+        "\t\t--fail-fast\t\tStop at the first test not passed",
+        "\t\t--isolation <MODE>\t\tReset between tests: process, restart, start (default) or continuous",
+        "\t\t--time-limit <MS>\t\tTime limit of the tests without one of their own",
+        "\t\t--json <PATH>\t\tWrite the report of the run as JSON",
+        "\t\t--junit <PATH>\t\tWrite the report of the run as JUnit XML",
+        "\t\t--notation <NOTATION>\t\tMoves and boards of failed tests as protocol (x,y, default) or algebraic (h8)",
+        "\t--test --compare <OLD_AI_PATH> <NEW_AI_PATH> [OPTIONS] [SUITE_PATH...]",
+        "\t\t\t\t\t\tRun the tests for both builds, then list what changed",
+        "\t\t\t\t\t\texiting with 1 if tests passing with <OLD_AI_PATH> now fail",
+        "\t\t\t\t\t\t--json and --junit reports are written for each build, suffixed -old and -new",
+        "\t--fight [<AI1_PATH> <AI2_PATH> [OPTIONS]]\t\tRun the fight ui, showing a game between both AIs live",
+        "\t\t\t\t\t\t`human` instead of an <AI_PATH> plays that side by clicking the board",
+        "\t\t--size <SIZE>\t\tSize of the board (20 by default)",
+        "\t\t--timeout <MS>\t\tTime each AI has to answer a move",
+        "\t\t--second\t\t<AI2_PATH> plays the first move",
+        "\t\t--opening <MOVES>\t\tMoves played before the AIs, such as \"h8 h9 j10\" or \"7,7 7,6\"",
+        "\t\t--record <PATH>\t\tSave the game as .psq, .sgf or .json",
+        "\t\t--trace <PATH>\t\tWrite the protocol trace of the game",
+        "\t--tournament <AI_PATH> <AI_PATH>... [OPTIONS]\t\tPlay a round robin between the AIs, showing its results live",
+        "\t\t--rounds <COUNT>\t\tGames of each pair of AIs, the first AI alternating (2 by default)",
+        "\t\t--concurrency <COUNT>\t\tGames played at the same time (1 by default)",
+        "\t\t\t\t\t\tand the options of --fight but --second, each game getting its own --record and --trace file",
+        "\t--view <RECORD> [--speed <MS>]\t\tStep through a .psq, .sgf or .json game record",
+        "\t\t\t\t\t\twith the arrows, Home/End, Space to auto-play a move every <MS> and +/-",
+        "\t--replay-trace <TRACE>\t\tCheck the referee replays <TRACE> identically",
     ];
 
     for message in messages {
@@ -57,17 +192,272 @@ fn show_help() {
     print!("{}", help)
 }
 
+/// Options and suite paths following the brain(s) of `--test`
+fn test_args(args: &[String]) -> Option<(Vec<String>, TestOptions)> {
+    let mut options = TestOptions::default();
+    let mut suite_paths = Vec::new();
+    let mut options_args = args.iter();
+    while let Some(arg) = options_args.next() {
+        match arg.as_str() {
+            "--symmetries" => options.symmetries = true,
+            "--list" => options.list = true,
+            "--fail-fast" => options.fail_fast = true,
+            "--filter" => {
+                let Some(filter) = options_args.next().map(|pattern| Filter::new(pattern)) else {
+                    println!("<PATTERN> expected after --filter!");
+                    return None;
+                };
+                match filter {
+                    Ok(filter) => options.filters.push(filter),
+                    Err(err) => {
+                        println!("{}", err);
+                        return None;
+                    }
+                }
+            }
+            "--translations" => options.translations = true,
+            "--json" | "--junit" => {
+                let Some(path) = options_args.next() else {
+                    println!("<PATH> expected after {}!", arg);
+                    return None;
+                };
+                if arg == "--json" {
+                    options.json = Some(path.into());
+                } else {
+                    options.junit = Some(path.into());
+                }
+            }
+            "--isolation" => {
+                let Some(Ok(isolation)) =
+                    options_args.next().map(|mode| Isolation::from_string(mode))
+                else {
+                    println!("<MODE> expected after --isolation (process, restart, start or continuous)!");
+                    return None;
+                };
+                options.isolation = isolation;
+            }
+            "--notation" => {
+                let Some(Ok(notation)) =
+                    options_args.next().map(|name| Notation::from_string(name))
+                else {
+                    println!("<NOTATION> expected after --notation (protocol or algebraic)!");
+                    return None;
+                };
+                options.notation = notation;
+            }
+            "--time-limit" => {
+                let Some(Ok(time_limit)) = options_args.next().map(|ms| ms.parse()) else {
+                    println!("<MS> expected after --time-limit!");
+                    return None;
+                };
+                options.time_limit = Some(Duration::from_millis(time_limit));
+            }
+            option if option.starts_with("--") => {
+                println!("Unknown option [{}]!", arg);
+                return None;
+            }
+            _ => suite_paths.push(arg.clone()),
+        }
+    }
+    if suite_paths.is_empty() {
+        suite_paths.push(DEFAULT_SUITES.to_string());
+    }
+
+    Some((suite_paths, options))
+}
+
+/// Brains and game options following `--fight` or `--tournament`
+struct GameArgs {
+    brains: Vec<String>,
+    settings: GameSettings,
+    rounds: Option<usize>,
+    concurrency: Option<usize>,
+}
+
+fn game_args(args: &[String]) -> Option<GameArgs> {
+    let mut game_args = GameArgs {
+        brains: Vec::new(),
+        settings: GameSettings {
+            board_size: DEFAULT_BOARD_SIZE,
+            ai1_starting: true,
+            timeout_turn: None,
+            trace: None,
+            record: None,
+            opening: Vec::new(),
+        },
+        rounds: None,
+        concurrency: None,
+    };
+    let settings = &mut game_args.settings;
+    let mut opening = None;
+    let mut options_args = args.iter();
+    while let Some(arg) = options_args.next() {
+        match arg.as_str() {
+            "--second" => settings.ai1_starting = false,
+            "--size" | "--rounds" | "--concurrency" => {
+                let Some(Ok(number)) = options_args.next().map(|number| number.parse()) else {
+                    println!("<NUMBER> expected after {}!", arg);
+                    return None;
+                };
+                match arg.as_str() {
+                    "--size" => settings.board_size = number,
+                    "--rounds" => game_args.rounds = Some(number),
+                    _ => game_args.concurrency = Some(number),
+                }
+            }
+            "--timeout" => {
+                let Some(Ok(timeout)) = options_args.next().map(|ms| ms.parse()) else {
+                    println!("<MS> expected after --timeout!");
+                    return None;
+                };
+                settings.timeout_turn = Some(Duration::from_millis(timeout));
+            }
+            "--opening" | "--record" | "--trace" => {
+                let Some(value) = options_args.next() else {
+                    println!("Value expected after {}!", arg);
+                    return None;
+                };
+                match arg.as_str() {
+                    "--opening" => opening = Some(value),
+                    "--record" => settings.record = Some(value.clone()),
+                    _ => settings.trace = Some(value.clone()),
+                }
+            }
+            option if option.starts_with("--") => {
+                println!("Unknown option [{}]!", arg);
+                return None;
+            }
+            _ => game_args.brains.push(arg.clone()),
+        }
+    }
+
+    // Algebraic moves depend on the size of the board, given in any order
+    if let Some(moves) = opening {
+        let parsed: Result<Vec<Location>, String> = moves
+            .split_whitespace()
+            .map(|coords| Location::parse(coords, settings.board_size))
+            .collect();
+        match parsed {
+            Ok(moves) => settings.opening = moves,
+            Err(err) => {
+                println!("{}", err);
+                return None;
+            }
+        }
+    }
+    if let Err(err) = settings.check_opening() {
+        println!("Invalid opening: {}!", err);
+        return None;
+    }
+
+    Some(game_args)
+}
+
+/// Brains and options following `--fight`
+fn fight_args(args: &[String]) -> Option<Fight> {
+    let GameArgs {
+        brains,
+        settings,
+        rounds,
+        concurrency,
+    } = game_args(args)?;
+    if rounds.is_some() || concurrency.is_some() {
+        println!("--rounds and --concurrency are only for --tournament!");
+        return None;
+    }
+    let [ai1, ai2] = brains.as_slice() else {
+        println!("<AI1_PATH> <AI2_PATH> expected!");
+        return None;
+    };
+
+    Some(Fight {
+        ai1: ai1.clone(),
+        ai2: ai2.clone(),
+        settings,
+    })
+}
+
+/// Brains and options following `--tournament`
+fn tournament_args(args: &[String]) -> Option<TournamentSettings> {
+    let game_args = game_args(args)?;
+    if game_args.brains.len() < 2 {
+        println!("At least two <AI_PATH> expected!");
+        return None;
+    }
+    if game_args.brains.iter().any(|brain| brain == HUMAN) {
+        println!("Tournaments are played by brains only!");
+        return None;
+    }
+    // The first brain alternates between games
+    if !game_args.settings.ai1_starting {
+        println!("--second is only for --fight!");
+        return None;
+    }
+
+    Some(TournamentSettings {
+        brains: game_args.brains,
+        rounds: game_args.rounds.unwrap_or(DEFAULT_ROUNDS),
+        concurrency: game_args.concurrency.unwrap_or(1),
+        game: game_args.settings,
+    })
+}
+
 fn check_args(args: Vec<String>) -> StartMode {
     match args[0].as_str() {
+        "--test" if args.get(1).is_some_and(|arg| arg == "--compare") => {
+            if args.len() < 4 {
+                println!("<OLD_AI_PATH> <NEW_AI_PATH> expected!");
+                return StartMode::Incorrect;
+            }
+
+            let Some((suite_paths, options)) = test_args(&args[4..]) else {
+                return StartMode::Incorrect;
+            };
+
+            StartMode::Compare(args[2].clone(), args[3].clone(), suite_paths, options)
+        }
         "--test" => {
-            if args.len() != 2 {
+            if args.len() < 2 {
                 println!("<AI_PATH> expected!");
                 return StartMode::Incorrect;
             }
 
-            StartMode::Test(args[1].clone())
+            let Some((suite_paths, options)) = test_args(&args[2..]) else {
+                return StartMode::Incorrect;
+            };
+
+            StartMode::Test(args[1].clone(), suite_paths, options)
+        }
+        "--fight" if args.len() == 1 => StartMode::Fight(None),
+        "--fight" => match fight_args(&args[1..]) {
+            Some(fight) => StartMode::Fight(Some(fight)),
+            None => StartMode::Incorrect,
+        },
+        "--tournament" => match tournament_args(&args[1..]) {
+            Some(settings) => StartMode::Tournament(settings),
+            None => StartMode::Incorrect,
+        },
+        "--view" => {
+            let interval = match &args[1..] {
+                [_] => Some(DEFAULT_INTERVAL),
+                [_, speed, ms] if speed == "--speed" => ms.parse().ok().map(Duration::from_millis),
+                _ => None,
+            };
+            let Some(interval) = interval else {
+                println!("<RECORD> [--speed <MS>] expected!");
+                return StartMode::Incorrect;
+            };
+
+            StartMode::View(args[1].clone(), interval)
+        }
+        "--replay-trace" => {
+            if args.len() != 2 {
+                println!("<TRACE> expected!");
+                return StartMode::Incorrect;
+            }
+
+            StartMode::ReplayTrace(args[1].clone())
         }
-        "--fight" => StartMode::Fight,
         _ => StartMode::Unknown,
     }
 }
@@ -75,7 +465,7 @@ fn check_args(args: Vec<String>) -> StartMode {
 fn main() {
     let args: Vec<String> = env::args().skip(1).collect();
 
-    if args.len() == 0 {
+    if args.is_empty() {
         return show_help();
     }
 
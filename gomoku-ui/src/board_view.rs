@@ -0,0 +1,228 @@
+use iced::alignment::{Horizontal, Vertical};
+use iced::mouse;
+use iced::widget::canvas::{
+    self, event, Cache, Cursor, Event, Frame, Geometry, Path, Stroke, Text,
+};
+use iced::{Color, Point, Rectangle, Size, Theme};
+
+use gomoku_core::board::{Board, Cell};
+use gomoku_core::location::{column_name, Location};
+
+use crate::Message;
+
+const WOOD: Color = Color::from_rgb(0.87, 0.72, 0.53);
+const GRID: Color = Color::from_rgb(0.25, 0.18, 0.1);
+const HIGHLIGHT: Color = Color::from_rgb(0.85, 0.1, 0.1);
+
+/// Board drawn on a canvas, with its move numbers, last move and winning line
+///
+/// Cells are sized to fit the canvas, whatever the size of the board.
+pub struct BoardView {
+    board: Board,
+    /// Moves in the order they were played, numbering the stones
+    moves: Vec<Location>,
+    winning_line: Option<Vec<Location>>,
+    /// Side whose stones are black
+    black: Cell,
+    cache: Cache,
+}
+
+impl BoardView {
+    pub fn new(size: usize) -> Self {
+        Self {
+            board: Board::new(size),
+            moves: Vec::new(),
+            winning_line: None,
+            black: Cell::Ai1,
+            cache: Cache::new(),
+        }
+    }
+
+    /// Show `board`, reached by playing `moves`
+    pub fn set(&mut self, board: Board, moves: Vec<Location>) {
+        self.winning_line = board.winning_line();
+        self.board = board;
+        self.moves = moves;
+        self.cache.clear();
+    }
+
+    /// Draw the stones of `cell` black, the other side's white
+    pub fn set_black(&mut self, cell: Cell) {
+        self.black = cell;
+        self.cache.clear();
+    }
+
+    /// Whether the stone on `cell` is drawn black
+    fn is_black(&self, cell: Cell) -> bool {
+        match cell {
+            Cell::NewAi1 => self.black == Cell::Ai1,
+            cell => cell == self.black,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Length of a cell and center of the top left intersection in `bounds`,
+    /// a cell being kept around the grid for the coordinates
+    fn layout(&self, bounds: Size) -> (f32, Point) {
+        let size = self.board.size().max(1) as f32;
+        let cell = bounds.width.min(bounds.height) / (size + 1.0);
+        let origin = Point::new(
+            (bounds.width - cell * (size - 1.0)) / 2.0,
+            (bounds.height - cell * (size - 1.0)) / 2.0,
+        );
+        (cell, origin)
+    }
+
+    /// Intersection nearest to `position`, none outside of the grid
+    fn location_at(&self, bounds: Size, position: Point) -> Option<Location> {
+        let (cell, origin) = self.layout(bounds);
+        let coord = |offset: f32| {
+            let index = (offset / cell).round();
+            (index >= 0.0 && (index as usize) < self.board.size()).then_some(index as usize)
+        };
+        Some(Location {
+            x: coord(position.x - origin.x)?,
+            y: coord(position.y - origin.y)?,
+        })
+    }
+
+    fn draw_board(&self, frame: &mut Frame) {
+        let (cell, origin) = self.layout(frame.size());
+        let size = self.board.size();
+        let center = |location: Location| {
+            Point::new(
+                origin.x + location.x as f32 * cell,
+                origin.y + location.y as f32 * cell,
+            )
+        };
+
+        frame.fill_rectangle(Point::ORIGIN, frame.size(), WOOD);
+
+        let grid = Stroke::default().with_width(1.0).with_color(GRID);
+        let last = size.saturating_sub(1);
+        for index in 0..size {
+            frame.stroke(
+                &Path::line(center((index, 0).into()), center((index, last).into())),
+                grid.clone(),
+            );
+            frame.stroke(
+                &Path::line(center((0, index).into()), center((last, index).into())),
+                grid.clone(),
+            );
+
+            // Algebraic coordinates, rows being counted from the bottom
+            let label_size = (cell * 0.4).max(8.0);
+            let column = center((index, last).into());
+            frame.fill_text(Text {
+                content: column_name(index),
+                position: Point::new(column.x, column.y + cell * 0.6),
+                color: GRID,
+                size: label_size,
+                horizontal_alignment: Horizontal::Center,
+                vertical_alignment: Vertical::Center,
+                ..Text::default()
+            });
+            let row = center((0, index).into());
+            frame.fill_text(Text {
+                content: (size - index).to_string(),
+                position: Point::new(row.x - cell * 0.6, row.y),
+                color: GRID,
+                size: label_size,
+                horizontal_alignment: Horizontal::Center,
+                vertical_alignment: Vertical::Center,
+                ..Text::default()
+            });
+        }
+
+        let radius = cell * 0.45;
+        for y in 0..size {
+            for x in 0..size {
+                let (stone, border) = match self.board.get(x, y) {
+                    Cell::Empty => continue,
+                    cell if self.is_black(cell) => (Color::BLACK, Color::BLACK),
+                    _ => (Color::WHITE, GRID),
+                };
+                let circle = Path::circle(center((x, y).into()), radius);
+                frame.fill(&circle, stone);
+                frame.stroke(
+                    &circle,
+                    Stroke::default().with_width(1.0).with_color(border),
+                );
+            }
+        }
+
+        for (index, location) in self.moves.iter().enumerate() {
+            let number = match self.board.get(location.x, location.y) {
+                Cell::Empty => continue,
+                cell if self.is_black(cell) => Color::WHITE,
+                _ => Color::BLACK,
+            };
+            let is_last = index + 1 == self.moves.len();
+            frame.fill_text(Text {
+                content: (index + 1).to_string(),
+                position: center(*location),
+                color: if is_last { HIGHLIGHT } else { number },
+                size: (cell * 0.4).max(6.0),
+                horizontal_alignment: Horizontal::Center,
+                vertical_alignment: Vertical::Center,
+                ..Text::default()
+            });
+            if is_last {
+                frame.stroke(
+                    &Path::circle(center(*location), radius),
+                    Stroke::default().with_width(2.0).with_color(HIGHLIGHT),
+                );
+            }
+        }
+
+        if let Some(line) = &self.winning_line {
+            if let (Some(first), Some(last)) = (line.first(), line.last()) {
+                frame.stroke(
+                    &Path::line(center(*first), center(*last)),
+                    Stroke::default()
+                        .with_width((cell * 0.15).max(2.0))
+                        .with_color(HIGHLIGHT),
+                );
+            }
+        }
+    }
+}
+
+impl canvas::Program<Message> for BoardView {
+    type State = ();
+
+    /// Clicked intersections, for the sides played by hand
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<Message>) {
+        let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return (event::Status::Ignored, None);
+        };
+        match cursor
+            .position_in(&bounds)
+            .and_then(|position| self.location_at(bounds.size(), position))
+        {
+            Some(location) => (event::Status::Captured, Some(Message::Clicked(location))),
+            None => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        vec![self
+            .cache
+            .draw(bounds.size(), |frame| self.draw_board(frame))]
+    }
+}
@@ -0,0 +1,242 @@
+use std::{collections::BTreeMap, path::Path, sync::mpsc, thread};
+
+use iced::futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver},
+    StreamExt,
+};
+
+use iced::widget::{scrollable, text, Canvas, Column, Row};
+use iced::{subscription, Element, Length, Subscription};
+
+use gomoku_core::{
+    board::Board,
+    game::GameEvent,
+    location::Location,
+    tournament::{Pairing, Tournament, TournamentEvent, TournamentSettings},
+};
+
+use crate::{BoardView, Message};
+
+/// Side of the board of the games being played
+const THUMBNAIL_SIZE: u16 = 200;
+const THUMBNAILS_PER_ROW: usize = 3;
+const COLUMN_WIDTH: u16 = 70;
+const NAME_WIDTH: u16 = 160;
+
+/// Game being played, shown small
+struct Thumbnail {
+    pairing: Pairing,
+    board: Board,
+    moves: Vec<Location>,
+    view: BoardView,
+}
+
+/// Live results of a tournament: standings, crosstable and games being played
+pub struct Dashboard {
+    settings: TournamentSettings,
+    tournament: Tournament,
+    games: BTreeMap<usize, Thumbnail>,
+    total: usize,
+    finished: bool,
+}
+
+impl Dashboard {
+    pub fn new(settings: TournamentSettings) -> Self {
+        Self {
+            tournament: Tournament::new(settings.brains.clone()),
+            total: Tournament::pairings(settings.brains.len(), settings.rounds).len(),
+            settings,
+            games: BTreeMap::new(),
+            finished: false,
+        }
+    }
+
+    pub fn on_event(&mut self, event: TournamentEvent) {
+        match event {
+            TournamentEvent::GameStarted(pairing) => {
+                let size = self.settings.game.board_size;
+                self.games.insert(
+                    pairing.game,
+                    Thumbnail {
+                        pairing,
+                        board: Board::new(size),
+                        moves: Vec::new(),
+                        view: BoardView::new(size),
+                    },
+                );
+            }
+            TournamentEvent::Game(game, event) => {
+                let Some(thumbnail) = self.games.get_mut(&game) else {
+                    return;
+                };
+                match event {
+                    GameEvent::Move { cell, location, .. } => {
+                        thumbnail.board.place(location.x, location.y, cell);
+                        thumbnail.moves.push(location);
+                    }
+                    GameEvent::TakenBack(location) => {
+                        thumbnail.board.remove(location.x, location.y);
+                        thumbnail.moves.pop();
+                    }
+                    GameEvent::Started { .. } | GameEvent::Ended(_) => return,
+                }
+                thumbnail
+                    .view
+                    .set(thumbnail.board.clone(), thumbnail.moves.clone());
+            }
+            TournamentEvent::GameEnded(result) => {
+                self.games.remove(&result.pairing.game);
+                self.tournament.push(result);
+            }
+            TournamentEvent::Finished => self.finished = true,
+        }
+    }
+
+    /// Short name of `brain`, the file name of its spec
+    fn name(&self, brain: usize) -> String {
+        let spec = &self.tournament.brains[brain];
+        Path::new(spec)
+            .file_name()
+            .map_or(spec.clone(), |name| name.to_string_lossy().to_string())
+    }
+
+    fn standings(&self) -> Element<'_, Message> {
+        let cell = |content: String| text(content).width(Length::Units(COLUMN_WIDTH));
+        let header = ["Score", "W/D/L", "Elo", "Crashes", "Timeouts"];
+        let mut table = Column::new().spacing(5).push(header.iter().fold(
+            Row::new().push(text("Brain").width(Length::Units(NAME_WIDTH))),
+            |row, title| row.push(cell(title.to_string())),
+        ));
+        for standing in self.tournament.standings() {
+            table = table.push(
+                Row::new()
+                    .push(text(self.name(standing.brain)).width(Length::Units(NAME_WIDTH)))
+                    .push(cell(format!("{}/{}", standing.score, standing.games)))
+                    .push(cell(format!(
+                        "{}/{}/{}",
+                        standing.wins, standing.draws, standing.losses
+                    )))
+                    .push(cell(format!("{:+.0}", standing.elo)))
+                    .push(cell(standing.crashes.to_string()))
+                    .push(cell(standing.timeouts.to_string())),
+            );
+        }
+        table.into()
+    }
+
+    fn crosstable(&self) -> Element<'_, Message> {
+        let brains = self.tournament.brains.len();
+        let cell = |content: String| text(content).width(Length::Units(COLUMN_WIDTH));
+        let mut header = Row::new().push(text("").width(Length::Units(NAME_WIDTH)));
+        for opponent in 0..brains {
+            header = header.push(cell(format!("#{}", opponent + 1)));
+        }
+        let mut table = Column::new().spacing(5).push(header);
+        for brain in 0..brains {
+            let mut row = Row::new().push(
+                text(format!("#{} {}", brain + 1, self.name(brain)))
+                    .width(Length::Units(NAME_WIDTH)),
+            );
+            for opponent in 0..brains {
+                let points = match self.tournament.crosstable(brain, opponent) {
+                    _ if brain == opponent => "x".to_string(),
+                    Some(points) => points.to_string(),
+                    None => "-".to_string(),
+                };
+                row = row.push(cell(points));
+            }
+            table = table.push(row);
+        }
+        table.into()
+    }
+
+    fn thumbnails(&self) -> Element<'_, Message> {
+        let mut grid = Column::new().spacing(10);
+        let games: Vec<&Thumbnail> = self.games.values().collect();
+        for chunk in games.chunks(THUMBNAILS_PER_ROW) {
+            let mut row = Row::new().spacing(10);
+            for thumbnail in chunk {
+                let pairing = thumbnail.pairing;
+                row = row.push(
+                    Column::new()
+                        .push(
+                            text(format!(
+                                "Game {}: {} vs {}",
+                                pairing.game + 1,
+                                self.name(pairing.first),
+                                self.name(pairing.second)
+                            ))
+                            .size(16),
+                        )
+                        .push(
+                            Canvas::new(&thumbnail.view)
+                                .width(Length::Units(THUMBNAIL_SIZE))
+                                .height(Length::Units(THUMBNAIL_SIZE)),
+                        ),
+                );
+            }
+            grid = grid.push(row);
+        }
+        grid.into()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let progress = if self.finished {
+            format!(
+                "Tournament over, {} games played",
+                self.tournament.results.len()
+            )
+        } else {
+            format!(
+                "{}/{} games played, {} in progress",
+                self.tournament.results.len(),
+                self.total,
+                self.games.len()
+            )
+        };
+        let results = Column::new()
+            .spacing(20)
+            .push(text(progress))
+            .push(self.standings())
+            .push(self.crosstable());
+
+        scrollable(Row::new().spacing(20).push(results).push(self.thumbnails())).into()
+    }
+
+    /// Play the tournament on its own thread, its events being turned into messages
+    pub fn subscription(&self) -> Subscription<Message> {
+        subscription::unfold(
+            "tournament",
+            Feed::Starting(self.settings.clone()),
+            |feed| async move {
+                match feed {
+                    Feed::Starting(settings) => {
+                        let (events, received) = mpsc::channel();
+                        let (forwarded, feed) = unbounded();
+                        thread::spawn(move || Tournament::run(&settings, events));
+                        // Waiting on the events would block the executor, they come through a thread
+                        thread::spawn(move || {
+                            for event in received {
+                                if forwarded.unbounded_send(event).is_err() {
+                                    break;
+                                }
+                            }
+                        });
+                        (None, Feed::Playing(feed))
+                    }
+                    Feed::Playing(mut events) => match events.next().await {
+                        Some(event) => (Some(Message::Tournament(event)), Feed::Playing(events)),
+                        None => (None, Feed::Over),
+                    },
+                    Feed::Over => iced::futures::future::pending().await,
+                }
+            },
+        )
+    }
+}
+
+enum Feed {
+    Starting(TournamentSettings),
+    Playing(UnboundedReceiver<TournamentEvent>),
+    Over,
+}
@@ -1,48 +1,432 @@
+mod board_view;
+mod dashboard;
+mod live;
+mod replay;
+
+use std::{
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
+
 use iced::executor;
-use iced::widget::{container, Column};
-use iced::{Application, Command, Element, Length, Settings, Theme};
+use iced::keyboard::{self, KeyCode};
+use iced::widget::{button, container, text, Canvas, Column, Row};
+use iced::{event, subscription, Event};
+use iced::{Application, Command, Element, Length, Settings, Subscription, Theme};
+
+use gomoku_core::{
+    board::{Board, Cell},
+    game::{GameEvent, GameResult},
+    location::Location,
+    player::PlayerMove,
+    record::GameRecord,
+    tournament::{TournamentEvent, TournamentSettings},
+};
+
+pub use board_view::BoardView;
+pub use dashboard::Dashboard;
+pub use live::Fight;
+pub use replay::{Replay, DEFAULT_INTERVAL};
+
+const DEFAULT_BOARD_SIZE: usize = 20;
+
+/// How often the status bar is refreshed while waiting for a move
+const REFRESH: Duration = Duration::from_millis(100);
+
+/// What the UI shows when it starts
+#[derive(Default)]
+pub enum Mode {
+    #[default]
+    Empty,
+    Fight(Fight),
+    /// Recorded game with the time between two moves of the auto-play
+    Replay(GameRecord, Duration),
+    Tournament(TournamentSettings),
+}
+
+pub struct Gomoku {
+    board: BoardView,
+    fight: Option<Fight>,
+    live: Option<LiveGame>,
+    /// Inputs of Ai 1 and Ai 2 when played by hand
+    humans: [Option<Sender<PlayerMove>>; 2],
+    /// Why the AIs of the fight couldn't be started
+    failure: Option<String>,
+    replay: Option<Replay>,
+    dashboard: Option<Dashboard>,
+}
+
+/// Game being played, as told by its events
+struct LiveGame {
+    board: Board,
+    moves: Vec<Location>,
+    /// Names of Ai 1 and Ai 2
+    players: [String; 2],
+    /// Side playing black
+    first: Cell,
+    to_move: Cell,
+    started: Instant,
+    turn_started: Instant,
+    /// Last `MESSAGE` or `DEBUG` line of Ai 1 and Ai 2
+    messages: [Option<String>; 2],
+    /// Result and length of the game once it is over
+    result: Option<(GameResult, Duration)>,
+}
+
+impl LiveGame {
+    fn new(board_size: usize, players: [String; 2], first: Cell) -> Self {
+        let now = Instant::now();
+        Self {
+            board: Board::new(board_size),
+            moves: Vec::new(),
+            players,
+            first,
+            to_move: first,
+            started: now,
+            turn_started: now,
+            messages: [None, None],
+            result: None,
+        }
+    }
+
+    /// Names of the black and white players
+    fn colours(&self) -> [&str; 2] {
+        match self.first {
+            Cell::Ai2 => [&self.players[1], &self.players[0]],
+            _ => [&self.players[0], &self.players[1]],
+        }
+    }
 
-pub struct Gomoku {}
+    fn player(&self, cell: Cell) -> &str {
+        match cell {
+            Cell::Ai2 => &self.players[1],
+            _ => &self.players[0],
+        }
+    }
+
+    fn status(&self) -> String {
+        if let Some((result, length)) = &self.result {
+            return format!("{} after {:.1}s", result, length.as_secs_f32());
+        }
+        format!(
+            "{} to move, move {} for {:.1}s (game {:.1}s)",
+            self.player(self.to_move),
+            self.moves.len() + 1,
+            self.turn_started.elapsed().as_secs_f32(),
+            self.started.elapsed().as_secs_f32()
+        )
+    }
+}
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum GameUpdate {
-    Board(),
+    /// Position reached by playing the moves, in order
+    Board(Board, Vec<Location>),
+    /// Event of the game being played
+    Event(GameEvent),
+    /// Inputs of the sides played by hand, Ai 1's then Ai 2's
+    Humans([Option<Sender<PlayerMove>>; 2]),
+    /// The AIs couldn't be started
+    Failed(String),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Message {
     GameUpdate(GameUpdate),
     UpdateView,
     Previous,
     Next,
+    /// Back to the empty board
+    Start,
+    /// Forward to the last move
+    End,
+    TogglePlay,
+    AutoPlay,
+    Faster,
+    Slower,
+    /// Intersection clicked on the board
+    Clicked(Location),
+    /// Take back the last move of the human to move
+    Undo,
+    Tournament(TournamentEvent),
 }
 
 impl Gomoku {
     pub fn run() {
         let _ = <Gomoku as Application>::run(Settings::default());
     }
+
+    /// Play `fight` and show it as it goes
+    pub fn fight(fight: Fight) {
+        let _ = <Gomoku as Application>::run(Settings::with_flags(Mode::Fight(fight)));
+    }
+
+    /// Step through `record`, auto-playing a move every `interval`
+    pub fn replay(record: GameRecord, interval: Duration) {
+        let _ = <Gomoku as Application>::run(Settings::with_flags(Mode::Replay(record, interval)));
+    }
+
+    /// Play a tournament and show its live results
+    pub fn tournament(settings: TournamentSettings) {
+        let _ = <Gomoku as Application>::run(Settings::with_flags(Mode::Tournament(settings)));
+    }
+
+    /// Apply a replay message, then show the position reached
+    fn on_replay(&mut self, message: Message) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+        match message {
+            Message::Previous => replay.previous(),
+            Message::Next => replay.next(),
+            Message::Start => replay.start(),
+            Message::End => replay.end(),
+            Message::TogglePlay => replay.toggle_play(),
+            Message::AutoPlay => replay.tick(),
+            Message::Faster => replay.faster(),
+            Message::Slower => replay.slower(),
+            _ => return,
+        }
+        self.board.set(replay.board(), replay.moves());
+    }
+
+    fn replay_controls(&self) -> Element<'_, Message> {
+        let Some(replay) = &self.replay else {
+            return Row::new().into();
+        };
+        let controls = [
+            ("|<", Message::Start),
+            ("<", Message::Previous),
+            (
+                if replay.playing { "||" } else { ">>" },
+                Message::TogglePlay,
+            ),
+            (">", Message::Next),
+            (">|", Message::End),
+            ("-", Message::Slower),
+            ("+", Message::Faster),
+        ];
+        let mut row = Row::new().spacing(5);
+        for (label, message) in controls {
+            row = row.push(button(text(label)).on_press(message));
+        }
+        row.into()
+    }
+
+    fn on_event(&mut self, event: GameEvent) {
+        match event {
+            GameEvent::Started {
+                board_size,
+                ai1,
+                ai2,
+                first,
+            } => {
+                self.live = Some(LiveGame::new(board_size, [ai1, ai2], first));
+                self.board.set(Board::new(board_size), Vec::new());
+                self.board.set_black(first);
+            }
+            GameEvent::Move {
+                cell,
+                location,
+                messages,
+                ..
+            } => {
+                let Some(live) = &mut self.live else {
+                    return;
+                };
+                live.board.place(location.x, location.y, cell);
+                live.moves.push(location);
+                live.to_move = if cell == Cell::Ai1 {
+                    Cell::Ai2
+                } else {
+                    Cell::Ai1
+                };
+                live.turn_started = Instant::now();
+                if let Some(message) = messages.last() {
+                    live.messages[cell.get_id().saturating_sub(1)] = Some(message.clone());
+                }
+                self.board.set(live.board.clone(), live.moves.clone());
+            }
+            GameEvent::TakenBack(location) => {
+                let Some(live) = &mut self.live else {
+                    return;
+                };
+                live.board.remove(location.x, location.y);
+                live.moves.pop();
+                live.to_move = if live.to_move == Cell::Ai1 {
+                    Cell::Ai2
+                } else {
+                    Cell::Ai1
+                };
+                live.turn_started = Instant::now();
+                self.board.set(live.board.clone(), live.moves.clone());
+            }
+            GameEvent::Ended(result) => {
+                if let Some(live) = &mut self.live {
+                    live.result = Some((result, live.started.elapsed()));
+                }
+            }
+        }
+    }
+
+    /// Input of the human to move, if any and the game isn't over
+    fn human_to_move(&self) -> Option<&Sender<PlayerMove>> {
+        let live = self.live.as_ref().filter(|live| live.result.is_none())?;
+        self.humans[live.to_move.get_id().checked_sub(1)?].as_ref()
+    }
+
+    fn on_human(&self, input: PlayerMove) {
+        let Some(human) = self.human_to_move() else {
+            return;
+        };
+        if let PlayerMove::Play(location) = input {
+            if !self.board.board().is_free(location.x, location.y) {
+                return;
+            }
+        }
+        // The game may have ended meanwhile, nothing is waiting for the move then
+        let _ = human.send(input);
+    }
+
+    fn fight_controls(&self) -> Element<'_, Message> {
+        if self.humans.iter().all(Option::is_none) {
+            return Row::new().into();
+        }
+        let mut undo = button(text("Undo"));
+        if self.human_to_move().is_some() {
+            undo = undo.on_press(Message::Undo);
+        }
+        Row::new().spacing(5).push(undo).into()
+    }
+
+    fn status_bar(&self) -> Element<'_, Message> {
+        if let Some(replay) = &self.replay {
+            return text(replay.status()).into();
+        }
+        let Some(live) = &self.live else {
+            let status = match (&self.failure, &self.fight) {
+                (Some(err), _) => format!("Failed to start the AIs: {}", err),
+                (None, Some(_)) => "Starting the AIs...".to_string(),
+                (None, None) => String::new(),
+            };
+            return text(status).into();
+        };
+
+        let [black, white] = live.colours();
+        let mut status = Column::new()
+            .spacing(5)
+            .push(text(format!("{} (black) vs {} (white)", black, white)))
+            .push(text(live.status()));
+        for (player, message) in live.players.iter().zip(&live.messages) {
+            if let Some(message) = message {
+                status = status.push(text(format!("{}: {}", player, message)).size(16));
+            }
+        }
+        status.into()
+    }
 }
 
 impl Application for Gomoku {
     type Message = Message;
     type Theme = Theme;
     type Executor = executor::Default;
-    type Flags = ();
+    type Flags = Mode;
 
-    fn new(_flags: ()) -> (Gomoku, Command<Message>) {
-        (Gomoku {}, Command::none())
+    fn new(mode: Mode) -> (Gomoku, Command<Message>) {
+        let mut gomoku = Gomoku {
+            board: BoardView::new(DEFAULT_BOARD_SIZE),
+            fight: None,
+            live: None,
+            humans: [None, None],
+            failure: None,
+            replay: None,
+            dashboard: None,
+        };
+        match mode {
+            Mode::Empty => {}
+            Mode::Fight(fight) => {
+                gomoku.board = BoardView::new(fight.settings.board_size);
+                gomoku.fight = Some(fight);
+            }
+            Mode::Replay(record, interval) => {
+                gomoku.board = BoardView::new(record.board_size);
+                gomoku.replay = Some(Replay::new(record, interval));
+            }
+            Mode::Tournament(settings) => gomoku.dashboard = Some(Dashboard::new(settings)),
+        }
+        (gomoku, Command::none())
     }
 
     fn title(&self) -> String {
         String::from("Gomoku player")
     }
 
-    fn update(&mut self, _message: Message) -> Command<Message> {
+    fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::GameUpdate(GameUpdate::Board(board, moves)) => self.board.set(board, moves),
+            Message::GameUpdate(GameUpdate::Event(event)) => self.on_event(event),
+            Message::GameUpdate(GameUpdate::Humans(humans)) => self.humans = humans,
+            Message::GameUpdate(GameUpdate::Failed(err)) => self.failure = Some(err),
+            Message::Clicked(location) => self.on_human(PlayerMove::Play(location)),
+            Message::Undo => self.on_human(PlayerMove::Undo),
+            Message::Tournament(event) => {
+                if let Some(dashboard) = &mut self.dashboard {
+                    dashboard.on_event(event);
+                }
+            }
+            // The status bar is rebuilt on every message
+            Message::UpdateView => {}
+            message => self.on_replay(message),
+        }
         Command::none()
     }
 
-    fn view(&self) -> Element<Message> {
-        container(Column::new())
+    fn subscription(&self) -> Subscription<Message> {
+        if let Some(fight) = &self.fight {
+            let playing = self.failure.is_none()
+                && self.live.as_ref().is_none_or(|live| live.result.is_none());
+            if !playing {
+                return live::subscription(fight.clone());
+            }
+            // The status bar counts the time taken by the move being waited for
+            return Subscription::batch([
+                live::subscription(fight.clone()),
+                iced::time::every(REFRESH).map(|_| Message::UpdateView),
+            ]);
+        }
+        if let Some(dashboard) = &self.dashboard {
+            return dashboard.subscription();
+        }
+        match &self.replay {
+            Some(replay) => Subscription::batch([
+                replay::subscription(replay),
+                subscription::events_with(replay_key),
+            ]),
+            None => Subscription::none(),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        if let Some(dashboard) = &self.dashboard {
+            return container(dashboard.view())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .padding(20)
+                .into();
+        }
+
+        let board = Canvas::new(&self.board)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let content = Column::new()
+            .spacing(10)
+            .push(board)
+            .push(self.replay_controls())
+            .push(self.fight_controls())
+            .push(self.status_bar());
+
+        container(content)
             .width(Length::Fill)
             .height(Length::Fill)
             .center_x()
@@ -51,3 +435,23 @@ impl Application for Gomoku {
             .into()
     }
 }
+
+/// Replay shortcuts: arrows to step, Home/End to jump, Space to auto-play and +/- for its speed
+fn replay_key(event: Event, status: event::Status) -> Option<Message> {
+    let Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) = event else {
+        return None;
+    };
+    if status == event::Status::Captured {
+        return None;
+    }
+    match key_code {
+        KeyCode::Left => Some(Message::Previous),
+        KeyCode::Right => Some(Message::Next),
+        KeyCode::Home | KeyCode::Up => Some(Message::Start),
+        KeyCode::End | KeyCode::Down => Some(Message::End),
+        KeyCode::Space => Some(Message::TogglePlay),
+        KeyCode::Plus | KeyCode::Equals | KeyCode::NumpadAdd => Some(Message::Faster),
+        KeyCode::Minus | KeyCode::NumpadSubtract => Some(Message::Slower),
+        _ => None,
+    }
+}
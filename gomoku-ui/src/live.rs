@@ -0,0 +1,115 @@
+use std::{
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use iced::futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver},
+    StreamExt,
+};
+use iced::{subscription, Subscription};
+
+use gomoku_core::{
+    ai::Ai,
+    game::{Game, GameSettings},
+    player::{Human, Player, PlayerMove, HUMAN},
+};
+
+use crate::{GameUpdate, Message};
+
+/// Game between two brains, or brains and humans, shown as it is played
+#[derive(Clone, Debug)]
+pub struct Fight {
+    /// Spec of the brain of each side, `human` to play it by hand
+    pub ai1: String,
+    pub ai2: String,
+    pub settings: GameSettings,
+}
+
+enum Feed {
+    Starting(Fight),
+    Playing(UnboundedReceiver<GameUpdate>),
+    Over,
+}
+
+/// Play `fight` on its own thread, its events being turned into messages
+pub fn subscription(fight: Fight) -> Subscription<Message> {
+    subscription::unfold("live-game", Feed::Starting(fight), |feed| async move {
+        match feed {
+            Feed::Starting(fight) => {
+                let (updates, humans) = start(fight);
+                (
+                    Some(Message::GameUpdate(GameUpdate::Humans(humans))),
+                    Feed::Playing(updates),
+                )
+            }
+            Feed::Playing(mut updates) => match updates.next().await {
+                Some(update) => (Some(Message::GameUpdate(update)), Feed::Playing(updates)),
+                None => (Some(Message::UpdateView), Feed::Over),
+            },
+            Feed::Over => iced::futures::future::pending().await,
+        }
+    })
+}
+
+/// Side started from `spec`, humans being none of the brains
+enum Side {
+    Human(Human),
+    Brain(String),
+}
+
+impl Side {
+    fn new(spec: &str) -> (Side, Option<Sender<PlayerMove>>) {
+        if spec != HUMAN {
+            return (Side::Brain(spec.to_string()), None);
+        }
+        let (human, moves) = Human::new(HUMAN);
+        (Side::Human(human), Some(moves))
+    }
+
+    fn player(self) -> Result<Player, String> {
+        match self {
+            Side::Human(human) => Ok(Player::Human(human)),
+            Side::Brain(spec) => Ai::from_spec(&spec).map(Player::Ai),
+        }
+    }
+}
+
+/// Play `fight` on its own thread, with the inputs of the sides played by hand
+///
+/// The updates come through a channel the UI can wait on without blocking its executor.
+fn start(
+    fight: Fight,
+) -> (
+    UnboundedReceiver<GameUpdate>,
+    [Option<Sender<PlayerMove>>; 2],
+) {
+    let (updates, receiver) = unbounded();
+    let (side1, human1) = Side::new(&fight.ai1);
+    let (side2, human2) = Side::new(&fight.ai2);
+    thread::spawn(move || {
+        let players = side1
+            .player()
+            .and_then(|player1| Ok((player1, side2.player()?)));
+        let mut game = match players {
+            Ok((player1, player2)) => Game::with_players(player1, player2),
+            Err(err) => {
+                eprintln!("Error whilst starting the AIs: [{}]", err);
+                let _ = updates.unbounded_send(GameUpdate::Failed(err));
+                return;
+            }
+        };
+        let (sender, events) = mpsc::channel();
+        game.set_events(Some(sender));
+        // The game blocks this thread, its events are forwarded from another one
+        thread::spawn(move || {
+            for event in events {
+                if updates.unbounded_send(GameUpdate::Event(event)).is_err() {
+                    break;
+                }
+            }
+        });
+        game.run(&fight.settings);
+    });
+    (receiver, [human1, human2])
+}
@@ -0,0 +1,13 @@
+use iced::Subscription;
+
+pub use gomoku_core::replay::{Replay, DEFAULT_INTERVAL};
+
+use crate::Message;
+
+/// Auto-play ticks of `replay`, none while paused
+pub fn subscription(replay: &Replay) -> Subscription<Message> {
+    if !replay.playing {
+        return Subscription::none();
+    }
+    iced::time::every(replay.interval).map(|_| Message::AutoPlay)
+}